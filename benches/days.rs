@@ -0,0 +1,51 @@
+//! Parse and solve times of every day on its real input and its example.
+//!
+//! Benchmark a single day with `cargo bench --bench days -- day4/`.
+
+use std::{fs, hint::black_box, path::PathBuf};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use aoc_2022::{inputs::InputStore, PUZZLES};
+
+/// Inputs for a day that are on disk, labeled by kind. Real inputs come from the inputs directory.
+fn inputs(store: &InputStore, day: u8) -> Vec<(&'static str, String)> {
+    [
+        ("real", store.path(day)),
+        (
+            "example",
+            PathBuf::from(format!("data/test_day_{day}_1.txt")),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(kind, fname)| fs::read_to_string(fname).ok().map(|input| (kind, input)))
+    .collect()
+}
+
+fn bench_days(c: &mut Criterion) {
+    let store = InputStore::from_env().unwrap_or_else(|err| panic!("No inputs directory. {err}"));
+    for puzzle in PUZZLES.iter() {
+        let mut group = c.benchmark_group(format!("day{}", puzzle.day));
+        // Some parts take seconds per iteration on the real input.
+        group.sample_size(10);
+
+        for (kind, input) in inputs(&store, puzzle.day) {
+            group.bench_function(format!("parse/{kind}"), |b| {
+                b.iter(|| puzzle.parse(black_box(&input)))
+            });
+
+            let solver = puzzle
+                .parse(&input)
+                .unwrap_or_else(|err| panic!("Can't benchmark {kind} input. {err}"));
+            for part in puzzle.parts() {
+                group.bench_function(format!("part{part}/{kind}"), |b| {
+                    b.iter(|| solver.solve(black_box(part)))
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_days);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+#![no_main]
+
+use aoc_2022::{Day13, Packet, Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        if let Ok(packet_pairs) = Day13::parse(input) {
+            // Comparing and printing recurse through nested lists, unlike parsing.
+            for (left, right) in packet_pairs.iter() {
+                let ordering = Packet::compare(left, right);
+                assert_eq!(Packet::compare(right, left), ordering.reverse());
+                for packet in [left, right] {
+                    let printed = packet.to_string();
+                    let reparsed = Packet::new(&printed).unwrap();
+                    assert_eq!(reparsed.to_string(), printed);
+                }
+            }
+        }
+    }
+});
@@ -1,115 +1,195 @@
-use std::collections::HashMap;
+use std::{
+    io,
+    io::Read,
+    ops::{Index, IndexMut},
+};
 
-use itertools::Itertools;
+use crate::days::{error::AocError, input, point::Point};
 
-#[derive(Debug)]
-pub struct GridString {
-    pub grid: String,
-    pub rows: usize,
-    pub cols: usize,
+/// Rectangular grid of cells stored row by row. Cells are addressed by `(row, col)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
 }
 
-impl GridString {
-    pub fn new(grid: &str) -> Result<GridString, &'static str> {
-        let rows = grid.lines().collect_vec();
-        // Assuming all rows are equal length.
-        if let Some(n_cols) = rows.get(0).and_then(|row| Some(row.len())) {
-            Ok(GridString {
-                grid: rows.join(""),
-                rows: rows.len(),
-                cols: n_cols,
-            })
-        } else {
-            Err("Empty grid provided.")
+impl<T> Grid<T> {
+    /// Parse rows of characters into a grid, mapping each character to a cell. Blank lines are skipped.
+    /// * Every row must be as long as the first.
+    /// * Characters that `cell` can't map are errors with `reason`.
+    pub fn parse(
+        text: &str,
+        reason: &str,
+        cell: impl Fn(char) -> Option<T>,
+    ) -> Result<Grid<T>, AocError> {
+        let parse_err = |line: usize, column: usize, text: &str, reason: String| AocError::Parse {
+            day: None,
+            line: line + 1,
+            column: column + 1,
+            text: text.to_string(),
+            reason,
+        };
+        let mut cells = vec![];
+        let mut rows = 0;
+        let mut cols = 0;
+
+        for (i, line) in input::non_blank_lines(text) {
+            let n_cols = line.chars().count();
+            if rows == 0 {
+                cols = n_cols;
+            } else if n_cols != cols {
+                return Err(parse_err(
+                    i,
+                    0,
+                    line,
+                    format!("Expected a row of {cols} characters."),
+                ));
+            }
+            for (col, char) in line.chars().enumerate() {
+                let Some(value) = cell(char) else {
+                    return Err(parse_err(i, col, &char.to_string(), reason.to_string()));
+                };
+                cells.push(value)
+            }
+            rows += 1;
+        }
+        if rows == 0 {
+            return Err(parse_err(0, 0, "", "Empty grid provided.".to_string()));
         }
+        Ok(Grid { cells, rows, cols })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, (row, col): (usize, usize)) -> Option<&T> {
+        (row < self.rows && col < self.cols).then(|| &self.cells[row * self.cols + col])
     }
-    pub fn search(&self, elem: char) -> Option<(usize, usize)> {
-        if let Some(idx) = self.grid.find(|char| char == elem) {
-            Some((idx / self.cols, idx % self.cols))
+
+    pub fn get_mut(&mut self, (row, col): (usize, usize)) -> Option<&mut T> {
+        (row < self.rows && col < self.cols).then(|| &mut self.cells[row * self.cols + col])
+    }
+
+    /// Cells of a row from left to right.
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.rows).then(|| &self.cells[row * self.cols..(row + 1) * self.cols])
+    }
+
+    /// Cells of a column from top to bottom. Empty if the column is out of bounds.
+    pub fn col(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        let start = if col < self.cols {
+            col
         } else {
-            None
-        }
+            self.cells.len()
+        };
+        self.cells[start..].iter().step_by(self.cols.max(1))
     }
-    pub fn search_all(&self, elem: char) -> Option<Vec<(usize, usize)>> {
-        let matching_chars = self
-            .grid
-            .chars()
+
+    /// Cells with their coordinates, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells
+            .iter()
             .enumerate()
-            .filter_map(|(idx, char)| {
-                if char == elem {
-                    Some((idx / self.cols, idx % self.cols))
-                } else {
-                    None
-                }
-            })
-            .collect_vec();
-        if !matching_chars.is_empty() {
-            Some(matching_chars)
-        } else {
-            None
-        }
+            .map(|(i, cell)| ((i / self.cols, i % self.cols), cell))
+    }
+
+    /// Coordinates of the first cell, row by row, that matches a predicate.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.positions(predicate).next()
     }
-    pub fn get_one(&self, row: usize, col: usize) -> Option<char> {
-        self.grid.chars().nth(row * self.cols + col)
+
+    /// Coordinates of every cell that matches a predicate.
+    pub fn positions<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.iter()
+            .filter_map(move |(pos, cell)| predicate(cell).then_some(pos))
     }
-    pub fn get(&self, row: Option<usize>, col: Option<usize>) -> Option<Vec<char>> {
-        let query = self
-            .grid
-            .chars()
-            .enumerate()
-            .filter_map(|(i, elem)| {
-                if let (Some(r), Some(c)) = (row, col) {
-                    ((i / self.cols) == r && (i % self.cols) == c).then_some(elem)
-                } else if let Some(r) = row {
-                    (i / self.cols == r).then_some(elem)
-                } else if let Some(c) = col {
-                    (i % self.cols == c).then_some(elem)
-                } else {
-                    None
-                }
-            })
-            .collect_vec();
-
-        // Check if query empty so as to never return empty vec.
-        if !query.is_empty() {
-            Some(query)
-        } else {
-            None
+
+    /// In-bounds grid index of a point.
+    pub fn index_of(&self, point: Point) -> Option<(usize, usize)> {
+        point
+            .grid_index()
+            .filter(|(row, col)| *row < self.rows && *col < self.cols)
+    }
+
+    /// In-bounds neighbours that share an edge with a cell, clockwise from above.
+    pub fn neighbours_4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Point::from_grid_index(pos)
+            .neighbours_4()
+            .filter_map(|point| self.index_of(point))
+    }
+
+    /// In-bounds neighbours of a cell including diagonals, clockwise from above.
+    pub fn neighbours_8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Point::from_grid_index(pos)
+            .neighbours_8()
+            .filter_map(|point| self.index_of(point))
+    }
+
+    /// Grid of the same shape with each cell mapped to a new value.
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            rows: self.rows,
+            cols: self.cols,
         }
     }
 }
 
-pub fn alphabet() -> HashMap<char, usize> {
-    let low_alpha = (b'a'..=b'z')
-        .filter_map(|c| {
-            let c = c as char;
-            if c.is_alphabetic() {
-                Some(c)
-            } else {
-                None
-            }
-        })
-        .enumerate()
-        .map(|(i, chr)| (chr, i + 1))
-        .collect::<HashMap<char, usize>>();
-
-    let mut alphabet = (b'A'..=b'Z')
-        .filter_map(|c| {
-            let c = c as char;
-            if c.is_alphabetic() {
-                Some(c)
-            } else {
-                None
-            }
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    /// Panics if the cell is out of bounds. Use `get` to check.
+    fn index(&self, pos: (usize, usize)) -> &Self::Output {
+        self.get(pos).unwrap_or_else(|| {
+            panic!(
+                "Cell {pos:?} is outside a {}x{} grid.",
+                self.rows, self.cols
+            )
         })
-        .enumerate()
-        .map(|(i, chr)| (chr, i + 27))
-        .collect::<HashMap<char, usize>>();
+    }
+}
 
-    // Join two alphabets
-    for (k, v) in low_alpha.into_iter() {
-        alphabet.insert(k, v);
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut Self::Output {
+        let (rows, cols) = (self.rows, self.cols);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("Cell {pos:?} is outside a {rows}x{cols} grid."))
     }
+}
+
+/// Read an entire puzzle input, from a file, stdin or a byte slice, into a string.
+pub fn read_input(mut reader: impl Read) -> Result<String, io::Error> {
+    let mut contents = String::new();
+    reader.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+#[test]
+fn test_grid() {
+    let grid = Grid::parse("123\n456\n", "Expected a digit.", |c| c.to_digit(10)).unwrap();
+    assert_eq!((grid.rows(), grid.cols()), (2, 3));
+    assert_eq!(grid[(1, 2)], 6);
+    assert_eq!(grid.row(1), Some([4, 5, 6].as_slice()));
+    assert_eq!(grid.col(1).rev().copied().collect::<Vec<u32>>(), [5, 2]);
+    assert_eq!(grid.position(|cell| *cell > 4), Some((1, 1)));
+    assert_eq!(
+        grid.neighbours_4((0, 0)).collect::<Vec<_>>(),
+        [(0, 1), (1, 0)]
+    );
+    assert_eq!(grid.neighbours_8((1, 1)).count(), 5);
 
-    alphabet
+    let err = Grid::parse("12\n4x\n", "Expected a digit.", |c| c.to_digit(10)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 2: Expected a digit. Found 'x'."
+    );
 }
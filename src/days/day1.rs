@@ -1,32 +1,607 @@
-use std::error::Error;
-use std::fs;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::mem;
 
 use itertools::Itertools;
+use pathfinding::{kuhn_munkres::kuhn_munkres, matrix::Matrix};
+use serde::Serialize;
+use tracing::debug;
 
-pub fn get_calories(fname: &str) -> Result<Vec<usize>, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
-    Ok(contents
-        .trim()
-        .split("\n\n")
-        .map(|cals| {
-            cals.split("\n")
-                .map(|num| num.parse::<usize>().expect("NaN"))
-                .sum::<usize>()
+use crate::days::{
+    common::read_input,
+    error::AocError,
+    input,
+    solution::{Answer, Solution},
+};
+
+/// Total calories carried by an elf and where the elf is in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfCalories {
+    /// Position of the elf in the input, from 0.
+    pub elf: usize,
+    pub calories: usize,
+}
+
+/// Calories of each item an elf carries.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Inventory {
+    pub items: Vec<usize>,
+    /// Total calories of the items.
+    pub calories: usize,
+}
+
+/// Collects each elf's items one line at a time.
+#[derive(Debug, Default)]
+struct CalorieCounter {
+    inventory: Inventory,
+}
+
+impl CalorieCounter {
+    /// Count a line of input numbered `i` from 0. Returns the elf's inventory once a blank line ends it.
+    fn add_line(&mut self, i: usize, line: &str) -> Result<Option<Inventory>, AocError> {
+        let item = line.trim();
+        if item.is_empty() {
+            return Ok(self.finish());
+        }
+        let calories = item.parse::<usize>().map_err(|_| {
+            AocError::parse_at(Day1::DAY, line, item, "Expected a number of calories.")
+                .offset_lines(i)
+        })?;
+        self.inventory.calories =
+            self.inventory
+                .calories
+                .checked_add(calories)
+                .ok_or_else(|| {
+                    AocError::parse_at(Day1::DAY, line, item, "Elf's total calories are too large.")
+                        .offset_lines(i)
+                })?;
+        self.inventory.items.push(calories);
+        Ok(None)
+    }
+
+    /// End the current elf's inventory and return it if the elf carries anything.
+    fn finish(&mut self) -> Option<Inventory> {
+        (!self.inventory.items.is_empty()).then(|| mem::take(&mut self.inventory))
+    }
+}
+
+/// Items carried by each elf. Each elf's items are separated from the next elf's by a blank line.
+pub fn parse_inventories(contents: &str) -> Result<Vec<Inventory>, AocError> {
+    let mut counter = CalorieCounter::default();
+    let mut inventories = vec![];
+
+    for (i, line) in input::lines(contents) {
+        inventories.extend(counter.add_line(i, line)?);
+    }
+    inventories.extend(counter.finish());
+
+    Ok(inventories)
+}
+
+pub fn read_inventories(reader: impl Read) -> Result<Vec<Inventory>, AocError> {
+    parse_inventories(&read_input(reader)?)
+}
+
+pub fn get_inventories(fname: &str) -> Result<Vec<Inventory>, AocError> {
+    read_inventories(File::open(fname)?)
+}
+
+/// Total calories carried by each elf.
+pub fn parse_calories(contents: &str) -> Result<Vec<usize>, AocError> {
+    let inventories = parse_inventories(contents)?;
+    Ok(inventories
+        .iter()
+        .map(|inventory| inventory.calories)
+        .collect())
+}
+
+pub fn read_calories(reader: impl Read) -> Result<Vec<usize>, AocError> {
+    parse_calories(&read_input(reader)?)
+}
+
+pub fn get_calories(fname: &str) -> Result<Vec<usize>, AocError> {
+    read_calories(File::open(fname)?)
+}
+
+/// Elves carrying the most calories of those pushed so far. Only the top `n` are kept, so memory stays bounded
+/// however many elves are pushed. Elves carrying the same calories rank in input order.
+#[derive(Debug, Clone)]
+pub struct TopElves {
+    n: usize,
+    // Min-heap of calories and elves so the lowest ranked elf is the one dropped.
+    heap: BinaryHeap<Reverse<(usize, Reverse<usize>)>>,
+}
+
+impl TopElves {
+    pub fn new(n: usize) -> Self {
+        TopElves {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    pub fn push(&mut self, elf: ElfCalories) {
+        self.heap.push(Reverse((elf.calories, Reverse(elf.elf))));
+        if self.heap.len() > self.n {
+            self.heap.pop();
+        }
+    }
+
+    /// Top elves, most calories first.
+    pub fn into_sorted(self) -> Vec<ElfCalories> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((calories, Reverse(elf)))| ElfCalories { elf, calories })
+            .collect()
+    }
+}
+
+/// Top `top_n` elves of an input read a line at a time, so the input is never held in memory.
+pub fn read_top_elves(reader: impl BufRead, top_n: usize) -> Result<Vec<ElfCalories>, AocError> {
+    let mut counter = CalorieCounter::default();
+    let mut top_elves = TopElves::new(top_n);
+    let mut n_elves = 0;
+    let mut push = |inventory: Inventory| {
+        top_elves.push(ElfCalories {
+            elf: n_elves,
+            calories: inventory.calories,
+        });
+        n_elves += 1;
+    };
+
+    for (i, line) in reader.lines().enumerate() {
+        if let Some(inventory) = counter.add_line(i, line?.trim_end())? {
+            push(inventory);
+        }
+    }
+    if let Some(inventory) = counter.finish() {
+        push(inventory);
+    }
+    Ok(top_elves.into_sorted())
+}
+
+pub fn top_calories(all_calories: &[usize], top_n: usize) -> usize {
+    let mut top_elves = TopElves::new(top_n);
+    for (elf, calories) in all_calories.iter().copied().enumerate() {
+        top_elves.push(ElfCalories { elf, calories });
+    }
+
+    let top_elves = top_elves.into_sorted();
+    for elf in top_elves.iter() {
+        debug!(
+            elf = elf.elf,
+            calories = elf.calories,
+            "Elf in the top {top_n}"
+        );
+    }
+    top_elves.iter().map(|elf| elf.calories).sum()
+}
+
+pub fn max_calories(fname: &str, top_n: usize) -> Result<usize, AocError> {
+    let top_elves = read_top_elves(BufReader::new(File::open(fname)?), top_n)?;
+    Ok(top_elves.iter().map(|elf| elf.calories).sum())
+}
+
+/// Percentiles of the elves' total calories in a report.
+pub const REPORT_PERCENTILES: [u8; 5] = [10, 25, 75, 90, 99];
+
+/// Bins of the histogram of elves' total calories in a report.
+pub const REPORT_BINS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Percentile {
+    pub percentile: u8,
+    pub calories: usize,
+}
+
+/// Number of elves whose total calories are in `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct HistogramBin {
+    pub start: usize,
+    pub end: usize,
+    pub n_elves: usize,
+}
+
+/// Largest single item and the first elf carrying one like it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LargestItem {
+    /// Position of the elf in the input, from 0.
+    pub elf: usize,
+    pub calories: usize,
+}
+
+/// Statistics of the calories carried by elves.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CalorieReport {
+    pub n_elves: usize,
+    pub total_calories: usize,
+    pub mean: f64,
+    /// Middle total, or the mean of the two middle totals.
+    pub median: f64,
+    /// Nearest-rank percentiles of the totals.
+    pub percentiles: Vec<Percentile>,
+    /// Number of items carried by each elf, in input order.
+    pub items_per_elf: Vec<usize>,
+    pub largest_item: Option<LargestItem>,
+    /// Equally wide bins of totals from the smallest total to the largest.
+    pub histogram: Vec<HistogramBin>,
+}
+
+impl CalorieReport {
+    pub fn new(inventories: &[Inventory]) -> Result<Self, AocError> {
+        let totals = inventories
+            .iter()
+            .map(|inventory| inventory.calories)
+            .sorted()
+            .collect_vec();
+        let (Some(min), Some(max)) = (totals.first().copied(), totals.last().copied()) else {
+            return Err(AocError::unsolvable(Day1::DAY, "No elves to report on."));
+        };
+        let n_elves = totals.len();
+        let total_calories = totals
+            .iter()
+            .try_fold(0usize, |sum, calories| sum.checked_add(*calories))
+            .ok_or_else(|| AocError::unsolvable(Day1::DAY, "Total calories are too large."))?;
+
+        let median = if n_elves % 2 == 1 {
+            totals[n_elves / 2] as f64
+        } else {
+            (totals[n_elves / 2 - 1] as f64 + totals[n_elves / 2] as f64) / 2.0
+        };
+        let percentiles = REPORT_PERCENTILES
+            .iter()
+            .map(|percentile| {
+                let rank = (*percentile as usize * n_elves).div_ceil(100).max(1);
+                Percentile {
+                    percentile: *percentile,
+                    calories: totals[rank - 1],
+                }
+            })
+            .collect();
+
+        let largest_item = inventories
+            .iter()
+            .enumerate()
+            .flat_map(|(elf, inventory)| inventory.items.iter().map(move |item| (elf, *item)))
+            .max_by_key(|(elf, calories)| (*calories, Reverse(*elf)))
+            .map(|(elf, calories)| LargestItem { elf, calories });
+
+        // Wide enough that the largest total falls in the last bin.
+        let bin_width = (max - min) / REPORT_BINS + 1;
+        let mut histogram = (0..REPORT_BINS)
+            .map(|i| {
+                let start = min.saturating_add(i * bin_width);
+                HistogramBin {
+                    start,
+                    end: start.saturating_add(bin_width),
+                    n_elves: 0,
+                }
+            })
+            .collect_vec();
+        for calories in totals.iter() {
+            histogram[(calories - min) / bin_width].n_elves += 1;
+        }
+
+        Ok(CalorieReport {
+            n_elves,
+            total_calories,
+            mean: total_calories as f64 / n_elves as f64,
+            median,
+            percentiles,
+            items_per_elf: inventories
+                .iter()
+                .map(|inventory| inventory.items.len())
+                .collect(),
+            largest_item,
+            histogram,
         })
-        .collect_vec())
+    }
+}
+
+pub fn calorie_report(fname: &str) -> Result<CalorieReport, AocError> {
+    CalorieReport::new(&get_inventories(fname)?)
+}
+
+/// Most items for which [`rebalance`] searches for an exact plan rather than a greedy one.
+pub const EXACT_REBALANCE_ITEMS: usize = 16;
+
+/// Most elves for which [`rebalance`] matches new loads to elves to keep the fewest items moving.
+pub const MATCHED_REBALANCE_ELVES: usize = 256;
+
+/// An item handed from one elf to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ItemMove {
+    pub calories: usize,
+    /// Positions of the elves in the input, from 0.
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Plan to redistribute items between elves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Rebalance {
+    /// Largest total calories carried by any elf after the moves.
+    pub max_calories: usize,
+    /// Total calories carried by each elf after the moves.
+    pub loads: Vec<usize>,
+    pub moves: Vec<ItemMove>,
+    /// Whether `max_calories` is the smallest possible, or only the result of a greedy heuristic.
+    pub exact: bool,
+}
+
+/// Assign items, largest first, to the elf carrying the least so far.
+fn rebalance_greedy(items: &[usize], n_elves: usize) -> Vec<Vec<usize>> {
+    let mut loads = (0..n_elves)
+        .map(|elf| Reverse((0, elf)))
+        .collect::<BinaryHeap<_>>();
+    let mut bins = vec![vec![]; n_elves];
+    for item in items {
+        if let Some(Reverse((load, elf))) = loads.pop() {
+            bins[elf].push(*item);
+            loads.push(Reverse((load + item, elf)));
+        }
+    }
+    bins
 }
 
-pub fn max_calories(fname: &str, top_n: usize) -> Result<usize, Box<dyn Error>> {
-    let mut all_calories = get_calories(fname)?;
+/// Branch and bound over the assignments of items, largest first, improving on `best`.
+fn rebalance_exact(items: &[usize], lower_bound: usize, best: &mut Vec<Vec<usize>>) {
+    fn max_load(bins: &[Vec<usize>]) -> usize {
+        bins.iter().map(|bin| bin.iter().sum()).max().unwrap_or(0)
+    }
 
-    all_calories.sort();
-    let mut max_cals: usize = 0;
-    for (i, cal) in all_calories.iter().rev().enumerate() {
-        println!("{}", cal);
-        if i == top_n {
-            break;
+    fn search(
+        items: &[usize],
+        loads: &mut [usize],
+        bins: &mut [Vec<usize>],
+        lower_bound: usize,
+        best_max: &mut usize,
+        best: &mut Vec<Vec<usize>>,
+    ) {
+        let Some((item, rest)) = items.split_first() else {
+            *best_max = loads.iter().copied().max().unwrap_or(0);
+            best.clone_from_slice(bins);
+            return;
+        };
+        let mut tried = vec![];
+        for elf in 0..loads.len() {
+            // Elves with the same load lead to the same plans.
+            if *best_max <= lower_bound || tried.contains(&loads[elf]) {
+                continue;
+            }
+            tried.push(loads[elf]);
+            if loads[elf] + item >= *best_max {
+                continue;
+            }
+            loads[elf] += item;
+            bins[elf].push(*item);
+            search(rest, loads, bins, lower_bound, best_max, best);
+            bins[elf].pop();
+            loads[elf] -= item;
         }
-        max_cals += *cal
     }
-    Ok(max_cals)
+
+    let mut best_max = max_load(best);
+    let mut loads = vec![0; best.len()];
+    let mut bins = vec![vec![]; best.len()];
+    search(
+        items,
+        &mut loads,
+        &mut bins,
+        lower_bound,
+        &mut best_max,
+        best,
+    );
+}
+
+/// Number of items in common between two lists sorted by calories.
+fn common_items(a: &[usize], b: &[usize]) -> usize {
+    let (mut i, mut j, mut common) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    common
+}
+
+/// Redistribute items between elves so the largest total any elf carries is as small as possible.
+///
+/// Plans with at most [`EXACT_REBALANCE_ITEMS`] items are exact. Larger ones assign the largest
+/// items first to the elf carrying the least, which is at most a third worse than the best plan.
+pub fn rebalance(inventories: &[Inventory]) -> Result<Rebalance, AocError> {
+    let n_elves = inventories.len();
+    inventories
+        .iter()
+        .try_fold(0usize, |sum, inventory| sum.checked_add(inventory.calories))
+        .ok_or_else(|| AocError::unsolvable(Day1::DAY, "Total calories are too large."))?;
+    let owned = inventories
+        .iter()
+        .map(|inventory| inventory.items.iter().copied().sorted().collect_vec())
+        .collect_vec();
+    let items = owned.iter().flatten().copied().sorted().rev().collect_vec();
+
+    let mut bins = rebalance_greedy(&items, n_elves);
+    let exact = items.len() <= EXACT_REBALANCE_ITEMS;
+    if exact {
+        let total = items.iter().sum::<usize>();
+        let lower_bound = total
+            .div_ceil(n_elves.max(1))
+            .max(items.first().copied().unwrap_or(0));
+        rebalance_exact(&items, lower_bound, &mut bins);
+    }
+    for bin in bins.iter_mut() {
+        bin.sort_unstable();
+    }
+
+    // Give each new load to the elf already carrying most of its items.
+    let bin_of_elf = if (1..=MATCHED_REBALANCE_ELVES).contains(&n_elves) {
+        let mut weights = Matrix::new(n_elves, n_elves, 0i64);
+        for (elf, items) in owned.iter().enumerate() {
+            for (bin, bin_items) in bins.iter().enumerate() {
+                weights[(elf, bin)] = common_items(items, bin_items) as i64;
+            }
+        }
+        kuhn_munkres(&weights).1
+    } else {
+        (0..n_elves).collect()
+    };
+
+    let mut leaving = vec![];
+    let mut arriving = vec![];
+    for (elf, bin) in bin_of_elf.iter().enumerate() {
+        let (had, gets) = (&owned[elf], &bins[*bin]);
+        let (mut i, mut j) = (0, 0);
+        while i < had.len() || j < gets.len() {
+            if j == gets.len() || (i < had.len() && had[i] < gets[j]) {
+                leaving.push((had[i], elf));
+                i += 1;
+            } else if i == had.len() || gets[j] < had[i] {
+                arriving.push((gets[j], elf));
+                j += 1;
+            } else {
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    leaving.sort_unstable();
+    arriving.sort_unstable();
+    let moves = leaving
+        .into_iter()
+        .zip(arriving)
+        .map(|((calories, from), (_, to))| ItemMove { calories, from, to })
+        .collect();
+
+    let loads = bin_of_elf
+        .iter()
+        .map(|bin| bins[*bin].iter().sum())
+        .collect_vec();
+    Ok(Rebalance {
+        max_calories: loads.iter().copied().max().unwrap_or(0),
+        loads,
+        moves,
+        exact,
+    })
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_calories(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(top_calories(input, 1).into())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(top_calories(input, 3).into())
+    }
+}
+
+#[test]
+fn test_calorie_overflow() {
+    let err = parse_calories(&format!("1000\n\n{}\n1\n", usize::MAX)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Day 1, line 4, column 1: Elf's total calories are too large. Found '1'."
+    );
+}
+
+#[test]
+fn test_top_elves() {
+    let input = "1000\n2000\n\n4000\n\n5000\n6000\n\n7000\n4000\n\n10000\n";
+    let top_elves = read_top_elves(input.as_bytes(), 2).unwrap();
+    assert_eq!(
+        top_elves,
+        [
+            ElfCalories {
+                elf: 2,
+                calories: 11000
+            },
+            ElfCalories {
+                elf: 3,
+                calories: 11000
+            }
+        ]
+    );
+    assert_eq!(
+        read_top_elves(input.replace('\n', "\r\n").as_bytes(), 2).unwrap(),
+        top_elves
+    );
+    assert_eq!(top_calories(&parse_calories(input).unwrap(), 3), 32000);
+    assert!(read_top_elves(input.as_bytes(), 0).unwrap().is_empty());
+}
+
+#[test]
+fn test_calorie_report() {
+    let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+    let report = CalorieReport::new(&parse_inventories(input).unwrap()).unwrap();
+    assert_eq!((report.n_elves, report.total_calories), (5, 55000));
+    assert_eq!((report.mean, report.median), (11000.0, 10000.0));
+    assert_eq!(
+        report
+            .percentiles
+            .iter()
+            .map(|percentile| percentile.calories)
+            .collect_vec(),
+        [4000, 6000, 11000, 24000, 24000]
+    );
+    assert_eq!(report.items_per_elf, [3, 1, 2, 3, 1]);
+    assert_eq!(
+        report.largest_item,
+        Some(LargestItem {
+            elf: 4,
+            calories: 10000
+        })
+    );
+    assert_eq!(
+        report.histogram.iter().map(|bin| bin.n_elves).collect_vec(),
+        [2, 0, 1, 1, 0, 0, 0, 0, 0, 1]
+    );
+    assert_eq!(report.histogram[9].end, 24010);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["largest_item"]["calories"], 10000);
+    assert!(CalorieReport::new(&[]).is_err());
+}
+
+#[test]
+fn test_rebalance() {
+    let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+    let inventories = parse_inventories(input).unwrap();
+    let plan = rebalance(&inventories).unwrap();
+    assert!(plan.exact);
+    assert_eq!(plan.max_calories, 11000);
+    assert_eq!(plan.loads.iter().sum::<usize>(), 55000);
+
+    let mut loads = inventories
+        .iter()
+        .map(|inventory| inventory.calories)
+        .collect_vec();
+    for ItemMove { calories, from, to } in plan.moves.iter() {
+        assert!(inventories[*from].items.contains(calories));
+        loads[*from] -= calories;
+        loads[*to] += calories;
+    }
+    assert_eq!(loads, plan.loads);
+
+    // Already balanced elves keep their items.
+    let balanced = parse_inventories("3\n\n1\n2\n\n3\n").unwrap();
+    assert_eq!(rebalance(&balanced).unwrap().moves, []);
+    assert_eq!(rebalance(&[]).unwrap().max_calories, 0);
 }
@@ -1,15 +1,21 @@
-use crate::days::error::ParserError;
+use crate::days::{
+    common::read_input,
+    error::AocError,
+    input,
+    solution::{Answer, Solution},
+};
 use std::cell::RefCell;
 use std::collections::VecDeque;
-use std::error::Error;
-use std::fs;
+use std::fs::File;
+use std::io::Read;
 use std::rc::Rc;
 use std::str::FromStr;
 
 use itertools::Itertools;
+use tracing::{debug, trace};
 
-#[derive(Debug, Clone, Copy)]
-enum Operation {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
     NoOp,
     Add(isize),
 }
@@ -23,54 +29,65 @@ impl Operation {
     }
 }
 
+/// Instruction as written in a program, like `addx -3`.
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::NoOp => write!(f, "noop"),
+            Operation::Add(amt) => write!(f, "addx {amt}"),
+        }
+    }
+}
+
 impl FromStr for Operation {
-    type Err = ParserError;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let split_instructions = s.trim().splitn(2, " ");
         // Try to split into a tuple of str slices.
         match split_instructions.collect_tuple::<(&str, &str)>() {
             Some((op, amt)) => {
-                if let Ok(reg_amt) = amt.parse::<isize>() {
-                    match op {
-                        "addx" => Ok(Operation::Add(reg_amt)),
-                        _ => Err(ParserError {
-                            reason: format!("Not implemented. {op}"),
-                        }),
-                    }
-                } else {
-                    Err(ParserError {
-                        reason: format!("Unable to convert register amount for {op} ({s})."),
-                    })
+                if op != "addx" {
+                    return Err(AocError::parse_at(
+                        Day10::DAY,
+                        s,
+                        op,
+                        "Expected 'addx' or 'noop'.",
+                    ));
                 }
+                amt.parse::<isize>().map(Operation::Add).map_err(|_| {
+                    AocError::parse_at(Day10::DAY, s, amt, "Expected a register amount.")
+                })
             }
-            None => match s {
+            None => match s.trim() {
                 "noop" => Ok(Operation::NoOp),
-                _ => Err(ParserError {
-                    reason: format!("Invalid single-word operation. {s}"),
-                }),
+                _ => Err(AocError::parse_at(
+                    Day10::DAY,
+                    s,
+                    s.trim(),
+                    "Expected 'addx' or 'noop'.",
+                )),
             },
         }
     }
 }
 
 #[derive(Debug)]
-struct CRT {
+pub struct Crt {
     screen: String,
     screen_width: usize,
     screen_height: usize,
     sprite_pos: Vec<usize>,
     lit_pixel: char,
-    empty_pixel: char,
 }
 
-impl CRT {
-    fn new() -> Self {
+impl Crt {
+    pub fn new() -> Self {
         let screen_width: usize = 40;
         let screen_height: usize = 6;
         let empty_pixel: char = '.';
 
-        CRT {
+        Crt {
             screen: (0..screen_width * screen_height)
                 .map(|_| empty_pixel)
                 .collect::<String>(),
@@ -78,33 +95,45 @@ impl CRT {
             screen_height,
             sprite_pos: vec![0, 1, 2],
             lit_pixel: '#',
-            empty_pixel,
         }
     }
 
-    /// Each cycle, the CRT draws a single pixel.
+    /// Each cycle, the Crt draws a single pixel. Cycles after the last pixel draw nothing.
     fn update_screen(&mut self, cycle: usize) {
         let cycle_pos = cycle % self.screen_width;
 
         // If register is at the sprite position, light pixel.
-        if self.sprite_pos.contains(&cycle_pos) {
+        if cycle < self.screen.len() && self.sprite_pos.contains(&cycle_pos) {
             self.screen
-                .replace_range(cycle..cycle, &self.lit_pixel.to_string())
+                .replace_range(cycle..cycle + 1, &self.lit_pixel.to_string())
         }
     }
-    fn update_sprite_pos(&mut self, register: usize) {
-        // Update sprite pos based on memory register.
-        self.sprite_pos = (register..=register + 2).collect_vec();
+    fn update_sprite_pos(&mut self, register: isize) {
+        // Update sprite pos based on memory register. Sprite is 3 pixels wide centered on the register.
+        // Saturating at the register's bounds only moves pixels that are far off the screen.
+        self.sprite_pos = (register.saturating_sub(1)..=register.saturating_add(1))
+            .filter_map(|pos| usize::try_from(pos).ok())
+            .collect_vec();
     }
 
-    fn display(&self) {
+    /// Each row of pixels from top to bottom.
+    pub fn rows(&self) -> Vec<String> {
         // Add extra 40 px width for tuple windows and additional step.
-        let screen = (0..(self.screen_width * self.screen_height) + self.screen_width)
+        (0..(self.screen_width * self.screen_height) + self.screen_width)
             .step_by(self.screen_width)
             .tuple_windows::<(usize, usize)>()
-            .map(|(start, stop)| self.screen.get(start..stop).unwrap())
-            .join("\n");
-        println!("{screen}");
+            .map(|(start, stop)| self.screen[start..stop].to_string())
+            .collect_vec()
+    }
+
+    pub fn render(&self) -> String {
+        self.rows().join("\n")
+    }
+}
+
+impl Default for Crt {
+    fn default() -> Self {
+        Crt::new()
     }
 }
 
@@ -115,7 +144,7 @@ struct Instruction {
 }
 
 #[derive(Debug)]
-struct SimpleCPU {
+pub struct SimpleCPU {
     register: isize,
     cycles: usize,
     instructions: VecDeque<Instruction>,
@@ -123,23 +152,33 @@ struct SimpleCPU {
 }
 
 impl SimpleCPU {
-    fn new() -> Self {
+    pub fn new() -> Self {
         SimpleCPU {
-            register: 0,
+            register: 1,
             cycles: 0,
             instructions: VecDeque::new(),
             register_history: Vec::new(),
         }
     }
 
-    fn complete_command(&mut self) {
+    fn complete_command(&mut self) -> Result<(), AocError> {
         let mut finished_instruction: Vec<usize> = vec![];
         for (i, instruction) in self.instructions.iter_mut().enumerate().rev() {
             // Remove instruction if stop cycle reached.
             if instruction.stop_cycle == self.cycles {
                 match instruction.operation {
                     Operation::NoOp => {}
-                    Operation::Add(amt) => self.register += amt,
+                    Operation::Add(amt) => {
+                        self.register = self.register.checked_add(amt).ok_or_else(|| {
+                            AocError::unsolvable(
+                                Day10::DAY,
+                                format!(
+                                    "Register {} overflows on {}.",
+                                    self.register, instruction.operation
+                                ),
+                            )
+                        })?
+                    }
                 };
                 finished_instruction.push(i)
             }
@@ -148,96 +187,210 @@ impl SimpleCPU {
         for i_instruction in finished_instruction.into_iter() {
             self.instructions.remove(i_instruction);
         }
+        Ok(())
     }
 
-    fn run_command(
+    pub fn run_operation(
         &mut self,
-        cmd_str: &str,
-        screen: Option<Rc<RefCell<CRT>>>,
-    ) -> Result<(), ParserError> {
-        let parsed_cmd = Operation::from_str(cmd_str);
-        if let Ok(parsed_cmd) = parsed_cmd {
-            // Init command.
-            self.instructions.push_back(Instruction {
-                operation: parsed_cmd,
-                stop_cycle: self.cycles + parsed_cmd.duration(),
-            });
-
-            // Increment cycles, store register value at timepoint, and then run command.
-            for _ in 0..parsed_cmd.duration() {
-                // Draw next pixel and update register position.
-                if let Some(screen) = screen.as_ref() {
-                    screen.borrow_mut().update_screen(self.cycles);
-                    screen.borrow().display();
-                }
+        operation: Operation,
+        screen: Option<Rc<RefCell<Crt>>>,
+    ) -> Result<(), AocError> {
+        // Init command.
+        self.instructions.push_back(Instruction {
+            operation,
+            stop_cycle: self.cycles + operation.duration(),
+        });
+
+        // Increment cycles, store register value at timepoint, and then run command.
+        for _ in 0..operation.duration() {
+            // Draw next pixel and update register position.
+            if let Some(screen) = screen.as_ref() {
+                screen.borrow_mut().update_screen(self.cycles);
+                trace!("Cycle {}\n{}", self.cycles, screen.borrow().render());
+            }
 
-                self.cycles += 1;
+            self.cycles += 1;
 
-                self.complete_command();
+            // Signal strength is measured during the cycle, before the command completes.
+            self.register_history.push((self.cycles, self.register));
 
-                // Update sprite_pos after completing command.
-                if let Some(screen) = screen.as_ref() {
-                    screen
-                        .borrow_mut()
-                        .update_sprite_pos(self.register.clamp(0, 100_000_000) as usize);
-                }
+            self.complete_command()?;
 
-                self.register_history
-                    .push((self.cycles, self.signal_strength()));
+            // Update sprite_pos after completing command.
+            if let Some(screen) = screen.as_ref() {
+                screen.borrow_mut().update_sprite_pos(self.register);
             }
-
-            Ok(())
-        } else {
-            Err(parsed_cmd.unwrap_err())
         }
+        Ok(())
     }
 
-    fn run_program(&mut self, fname: &str, screen: Rc<RefCell<CRT>>) -> Result<(), Box<dyn Error>> {
-        let contents = fs::read_to_string(fname)?;
-        for instruction in contents.lines() {
-            self.run_command(instruction, Some(screen.clone()))?;
+    pub fn run_command(
+        &mut self,
+        cmd_str: &str,
+        screen: Option<Rc<RefCell<Crt>>>,
+    ) -> Result<(), AocError> {
+        let parsed_cmd = Operation::from_str(cmd_str)?;
+        self.run_operation(parsed_cmd, screen)
+    }
+
+    /// Run each line of a program's source.
+    pub fn run_source(
+        &mut self,
+        source: &str,
+        screen: Option<Rc<RefCell<Crt>>>,
+    ) -> Result<(), AocError> {
+        for (i, instruction) in input::non_blank_lines(source) {
+            self.run_command(instruction, screen.clone())
+                .map_err(|err| err.offset_lines(i))?;
         }
 
         Ok(())
     }
 
-    fn signal_strength(&self) -> isize {
-        self.register * self.cycles as isize
+    pub fn run_reader(
+        &mut self,
+        reader: impl Read,
+        screen: Option<Rc<RefCell<Crt>>>,
+    ) -> Result<(), AocError> {
+        self.run_source(&read_input(reader)?, screen)
+    }
+
+    pub fn run_program(&mut self, fname: &str, screen: Rc<RefCell<Crt>>) -> Result<(), AocError> {
+        self.run_reader(File::open(fname)?, Some(screen))
+    }
+
+    pub fn signal_strength(&self) -> Result<isize, AocError> {
+        signal_strength(self.cycles, self.register)
+    }
+
+    pub fn register(&self) -> isize {
+        self.register
+    }
+
+    pub fn cycles(&self) -> usize {
+        self.cycles
+    }
+
+    /// Register recorded during each cycle.
+    pub fn register_history(&self) -> &[(usize, isize)] {
+        &self.register_history
     }
 }
 
-pub fn cathode_cpu(fname: &str) -> Result<isize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
+impl Default for SimpleCPU {
+    fn default() -> Self {
+        SimpleCPU::new()
+    }
+}
+
+/// Signal strength of the register during a cycle, unless it's too large for an `isize`.
+fn signal_strength(cycle: usize, register: isize) -> Result<isize, AocError> {
+    isize::try_from(cycle)
+        .ok()
+        .and_then(|cycle| register.checked_mul(cycle))
+        .ok_or_else(|| {
+            AocError::unsolvable(
+                Day10::DAY,
+                format!(
+                    "Signal strength of register {register} during cycle {cycle} is too large."
+                ),
+            )
+        })
+}
+
+pub fn parse_program(contents: &str) -> Result<Vec<Operation>, AocError> {
+    input::non_blank_lines(contents)
+        .map(|(i, line)| Operation::from_str(line).map_err(|err| err.offset_lines(i)))
+        .collect()
+}
+
+pub fn signal_strength_sum(program: &[Operation]) -> Result<isize, AocError> {
     let mut cpu = SimpleCPU::new();
 
     let mut cycle_checkpoints: VecDeque<usize> = (60..500).step_by(40).collect();
     cycle_checkpoints.push_front(20);
 
-    for instruction in contents.lines() {
-        cpu.run_command(instruction, None)?;
-    }
-    // Print out timepoints in register history.
-    let agg_signal: isize = cpu
-        .register_history
-        .iter()
-        .filter_map(|(cycle, signal)| {
-            if cycle_checkpoints.contains(cycle) {
-                println!("{},{}", cycle, signal);
-                Some(signal)
-            } else {
-                None
-            }
-        })
-        .sum();
+    for operation in program.iter() {
+        cpu.run_operation(*operation, None)?;
+    }
+    // Sum timepoints in register history.
+    let mut sum: isize = 0;
+    for (cycle, register) in cpu.register_history.iter() {
+        if cycle_checkpoints.contains(cycle) {
+            let signal = signal_strength(*cycle, *register)?;
+            debug!("Cycle {cycle} signal strength {signal}");
+            sum = sum.checked_add(signal).ok_or_else(|| {
+                AocError::unsolvable(Day10::DAY, "Sum of signal strengths is too large.")
+            })?;
+        }
+    }
+    Ok(sum)
+}
+
+/// Rows of the screen drawn while running a program.
+pub fn render_program(program: &[Operation]) -> Result<Vec<String>, AocError> {
+    let screen = Rc::new(RefCell::new(Crt::new()));
+    let mut cpu = SimpleCPU::new();
 
-    Ok(agg_signal)
+    for operation in program.iter() {
+        cpu.run_operation(*operation, Some(screen.clone()))?;
+    }
+
+    let rows = screen.borrow().rows();
+    Ok(rows)
 }
 
-pub fn race_the_beam(fname: &str) -> Result<(), Box<dyn Error>> {
-    let screen = Rc::new(RefCell::new(CRT::new()));
+pub fn read_program(reader: impl Read) -> Result<Vec<Operation>, AocError> {
+    parse_program(&read_input(reader)?)
+}
+
+pub fn cathode_cpu(fname: &str) -> Result<isize, AocError> {
+    signal_strength_sum(&read_program(File::open(fname)?)?)
+}
+
+pub fn race_the_beam(fname: &str) -> Result<Vec<String>, AocError> {
+    let screen = Rc::new(RefCell::new(Crt::new()));
     let mut cpu = SimpleCPU::new();
 
     cpu.run_program(fname, screen.clone())?;
 
-    Ok(())
+    let rows = screen.borrow().rows();
+    Ok(rows)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    type Input = Vec<Operation>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_program(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(signal_strength_sum(input)?.into())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(Answer::Image(render_program(input)?))
+    }
+}
+
+#[test]
+fn test_register_overflow() {
+    let program = parse_program(&format!("addx {}\naddx 1\n", isize::MAX - 1)).unwrap();
+    assert!(matches!(
+        signal_strength_sum(&program),
+        Err(AocError::Unsolvable { day: 10, .. })
+    ));
+    assert!(render_program(&program).is_err());
+
+    // A register near its bound only matters to the cycles that are checked.
+    let program = parse_program(&format!("addx {}\nnoop\n", isize::MIN + 1)).unwrap();
+    assert_eq!(signal_strength_sum(&program).unwrap(), 0);
+    assert!(render_program(&program).is_ok());
+    let program =
+        parse_program(&format!("addx {}\n{}", isize::MAX / 2, "noop\n".repeat(20))).unwrap();
+    assert!(signal_strength_sum(&program).is_err());
 }
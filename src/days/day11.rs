@@ -1,11 +1,17 @@
 use std::cell::RefCell;
-use std::error::Error;
+use std::fs::File;
+use std::io::Read;
 use std::rc::Rc;
-use std::{fs, vec};
+use std::vec;
 
 use itertools::Itertools;
 
-use super::error::ParserError;
+use super::{
+    common::read_input,
+    error::AocError,
+    input,
+    solution::{Answer, Solution},
+};
 
 #[derive(Debug, PartialEq, Clone)]
 enum Operand {
@@ -13,7 +19,7 @@ enum Operand {
     Value(usize),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Operation {
     Add,
     Sub,
@@ -21,51 +27,46 @@ enum Operation {
     Div,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Statement {
     operation: Operation,
     operand_1: Operand,
     operand_2: Operand,
 }
 
-impl Statement {
-    fn result(&self, input: Option<usize>) -> Result<usize, &'static str> {
-        if (self.operand_1 == Operand::Old || self.operand_2 == Operand::Old) && input.is_none() {
-            return Err("Cannot perform operation without an input value.");
-        }
-        // Set operands if some input is provided.
-        let (mut op_1, mut op_2) = (self.operand_1.clone(), self.operand_2.clone());
-        if let Some(input) = input {
-            if self.operand_1 == Operand::Old {
-                op_1 = Operand::Value(input);
-            }
-            if self.operand_2 == Operand::Old {
-                op_2 = Operand::Value(input);
-            }
+impl Operand {
+    /// Value of the operand given the old worry level.
+    fn value(&self, old: usize) -> usize {
+        match self {
+            Operand::Old => old,
+            Operand::Value(value) => *value,
         }
-        if let (Operand::Value(v1), Operand::Value(v2)) = (op_1, op_2) {
-            Ok(match self.operation {
-                Operation::Add => v1 + v2,
-                // Worry level for input item cannot be less than 0.
-                Operation::Sub => v1.saturating_sub(v2),
-                Operation::Mult => v1 * v2,
-                Operation::Div => v1 / v2,
-            })
-        } else {
-            Err("Value for op1 or op2 wasn't properly set.")
+    }
+}
+
+impl Statement {
+    /// New worry level of an item from its old worry level, unless it's too large for a `usize` or divides by zero.
+    fn result(&self, old: usize) -> Option<usize> {
+        let (v1, v2) = (self.operand_1.value(old), self.operand_2.value(old));
+        match self.operation {
+            Operation::Add => v1.checked_add(v2),
+            // Worry level for input item cannot be less than 0.
+            Operation::Sub => Some(v1.saturating_sub(v2)),
+            Operation::Mult => v1.checked_mul(v2),
+            Operation::Div => v1.checked_div(v2),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct ThrowTest {
     modulus: usize,
     on_true: usize,
     on_false: usize,
 }
 
-#[derive(Debug)]
-struct Monkey {
+#[derive(Debug, Clone)]
+pub struct Monkey {
     num: usize,
     items: Vec<usize>,
     inspected_items: usize,
@@ -73,96 +74,169 @@ struct Monkey {
     throw_test: ThrowTest,
 }
 impl Monkey {
-    pub fn new(behavior: &str) -> Result<Self, ParserError> {
-        Monkey::_parse_behavior(behavior)
+    pub fn new(behavior: &str) -> Result<Self, AocError> {
+        let lines = input::lines(behavior).map(|(_, line)| line).collect_vec();
+        Monkey::_parse_behavior(&lines, None)
+    }
+
+    pub fn num(&self) -> usize {
+        self.num
+    }
+
+    /// Worry levels of the items the monkey is holding.
+    pub fn items(&self) -> &[usize] {
+        &self.items
     }
 
-    /// Parse the 2nd string from line split into two strings into a `usize`.
-    /// * **Note**: Will return `Option::None` if multiple delimiters are present.
+    pub fn inspected_items(&self) -> usize {
+        self.inspected_items
+    }
+
+    /// Parse the number after a delimiter in a line into a `usize`.
     ///
     /// Examples:
-    /// * `(Monkey 0)`
+    /// * `(Monkey 0):`
     /// * `divisible (by 19)`
     /// * `If true: throw to (monkey 2)`
-    fn _parse_num_label(delim: &str, line: &str) -> Option<usize> {
-        if let Some((_, str_num)) = line.split(delim).collect_tuple::<(&str, &str)>() {
-            return str_num.trim().parse::<usize>().ok();
+    fn _parse_num_label(delim: &str, line: &str) -> Result<usize, AocError> {
+        let Some((_, str_num)) = line.split_once(delim) else {
+            return Err(AocError::parse(
+                Day11::DAY,
+                line,
+                format!("Expected '{delim}' followed by a number."),
+            ));
+        };
+        let str_num = str_num.trim().trim_end_matches(':');
+        str_num
+            .parse::<usize>()
+            .map_err(|_| AocError::parse_at(Day11::DAY, line, str_num, "Expected a number."))
+    }
+    /// Parse the monkey to throw to from line. If the thrower's position among `n_monkeys` is known
+    /// as `(position, n_monkeys)`, the target must be another of those monkeys.
+    ///
+    /// Ex. `If true: throw to monkey (2)`
+    fn _parse_target(line: &str, thrower: Option<(usize, usize)>) -> Result<usize, AocError> {
+        let target = Monkey::_parse_num_label("monkey", line)?;
+        let Some((position, n_monkeys)) = thrower else {
+            return Ok(target);
+        };
+        let str_target = line
+            .split_once("monkey")
+            .map_or(line, |(_, str_num)| str_num.trim().trim_end_matches(':'));
+        if target >= n_monkeys {
+            Err(AocError::parse_at(
+                Day11::DAY,
+                line,
+                str_target,
+                format!("Expected one of the {n_monkeys} monkeys."),
+            ))
+        } else if target == position {
+            Err(AocError::parse_at(
+                Day11::DAY,
+                line,
+                str_target,
+                "A monkey can't throw to itself.",
+            ))
         } else {
-            None
+            Ok(target)
         }
     }
     /// Parse monkey items from line.
     ///
     /// Ex. `Starting items: (54, 65, 75, 74)`
-    fn _parse_items(line: &str) -> Option<Vec<usize>> {
-        if let Some((_, str_items)) = line.split(':').collect_tuple::<(&str, &str)>() {
-            return str_items
-                .split(',')
-                .map(|str_item| str_item.trim().parse::<usize>().ok())
-                .collect();
+    fn _parse_items(line: &str) -> Result<Vec<usize>, AocError> {
+        let Some((_, str_items)) = line.split_once(':') else {
+            return Err(AocError::parse(
+                Day11::DAY,
+                line,
+                "Expected ':' followed by a list of items.",
+            ));
+        };
+        if str_items.trim().is_empty() {
+            return Ok(vec![]);
+        }
+        str_items
+            .split(',')
+            .map(|str_item| {
+                let str_item = str_item.trim();
+                str_item.parse::<usize>().map_err(|_| {
+                    AocError::parse_at(
+                        Day11::DAY,
+                        line,
+                        str_item,
+                        "Expected an item's worry level.",
+                    )
+                })
+            })
+            .collect()
+    }
+    /// Parse an operand of a monkey operation.
+    fn _parse_operand(line: &str, operand: &str) -> Result<Operand, AocError> {
+        if operand == "old" {
+            Ok(Operand::Old)
         } else {
-            None
+            operand.parse::<usize>().map(Operand::Value).map_err(|_| {
+                AocError::parse_at(Day11::DAY, line, operand, "Expected 'old' or a number.")
+            })
         }
     }
     /// Parse monkey operation from line.
-    /// * Panics if operation is unknown or values cannot be cast to `usize`.
     ///
     /// Ex. `Operation: new = old + 6`
-    fn _parse_operation(line: &str) -> Result<Statement, ParserError> {
-        if let Some((_, str_ops)) = line.split('=').collect_tuple::<(&str, &str)>() {
-            let (op1, oper, op2) = str_ops
-                .trim()
-                .split(' ')
-                .collect_tuple::<(&str, &str, &str)>()
-                .unwrap();
-
-            let operation = match oper {
-                "+" => Operation::Add,
-                "-" => Operation::Sub,
-                "*" => Operation::Mult,
-                "/" => Operation::Div,
-                _ => {
-                    return Err(ParserError {
-                        reason: format!("Unknown operation '{oper}'."),
-                    });
-                }
-            };
-            let parsed_op1 = if op1 == "old" {
-                Operand::Old
-            } else {
-                if let Ok(parsed_value) = op1.parse::<usize>() {
-                    Operand::Value(parsed_value)
-                } else {
-                    return Err(ParserError {
-                        reason: format!("Operand 1 ({op1}) cannot be converted to uint."),
-                    });
-                }
-            };
-            let parsed_op2 = if op2 == "old" {
-                Operand::Old
-            } else {
-                if let Ok(parsed_value) = op2.parse::<usize>() {
-                    Operand::Value(parsed_value)
-                } else {
-                    return Err(ParserError {
-                        reason: format!("Operand 2 ({op2}) cannot be converted to uint."),
-                    });
-                }
-            };
+    fn _parse_operation(line: &str) -> Result<Statement, AocError> {
+        let Some((_, str_ops)) = line.split_once('=') else {
+            return Err(AocError::parse(
+                Day11::DAY,
+                line,
+                "Missing '=' delimiter in line.",
+            ));
+        };
+        let Some((op1, oper, op2)) = str_ops.split_whitespace().collect_tuple() else {
+            return Err(AocError::parse_at(
+                Day11::DAY,
+                line,
+                str_ops.trim(),
+                "Expected an operation like 'old + 6'.",
+            ));
+        };
 
-            Ok(Statement {
-                operation,
-                operand_1: parsed_op1,
-                operand_2: parsed_op2,
-            })
-        } else {
-            Err(ParserError {
-                reason: "Missing '=' delimiter in line.".to_string(),
-            })
+        let operation = match oper {
+            "+" => Operation::Add,
+            "-" => Operation::Sub,
+            "*" => Operation::Mult,
+            "/" => Operation::Div,
+            _ => {
+                return Err(AocError::parse_at(
+                    Day11::DAY,
+                    line,
+                    oper,
+                    "Expected an operator (+, -, * or /).",
+                ));
+            }
+        };
+        let operand_1 = Monkey::_parse_operand(line, op1)?;
+        let operand_2 = Monkey::_parse_operand(line, op2)?;
+        if matches!(operation, Operation::Div) && operand_2 == Operand::Value(0) {
+            return Err(AocError::parse_at(
+                Day11::DAY,
+                line,
+                op2,
+                "Can't divide by zero.",
+            ));
         }
+
+        Ok(Statement {
+            operation,
+            operand_1,
+            operand_2,
+        })
     }
-    /// Parse monkey behavior into a `Monkey`.
-    fn _parse_behavior(behavior: &str) -> Result<Monkey, ParserError> {
+    /// Parse the lines of a monkey's behavior into a `Monkey`.
+    /// * `thrower` is the monkey's position among all the monkeys and their number, if known.
+    fn _parse_behavior(
+        behavior: &[&str],
+        thrower: Option<(usize, usize)>,
+    ) -> Result<Monkey, AocError> {
         let mut num: Option<usize> = None;
         let mut items: Option<Vec<usize>> = None;
         let mut stmt: Option<Statement> = None;
@@ -171,113 +245,195 @@ impl Monkey {
         let mut monkey_true: Option<usize> = None;
         let mut monkey_false: Option<usize> = None;
 
-        for line in behavior.lines() {
-            let line = line.trim();
-            if line.starts_with("Monkey") {
-                num = Monkey::_parse_num_label("Monkey", &line.replace(":", ""))
-            } else if line.starts_with("Starting items") {
-                items = Monkey::_parse_items(line)
-            } else if line.starts_with("Operation") {
-                stmt = Monkey::_parse_operation(line).ok()
-            } else if line.starts_with("Test") {
-                test_modulus = Monkey::_parse_num_label("by", line)
-            } else if line.starts_with("If true") {
-                monkey_true = Monkey::_parse_num_label("monkey", line)
-            } else if line.starts_with("If false") {
-                monkey_false = Monkey::_parse_num_label("monkey", line)
-            }
-        }
-        if let (Some(div), Some(m_true), Some(m_false)) = (test_modulus, monkey_true, monkey_false)
-        {
-            if let (Some(n), Some(items), Some(stmt)) = (num, items, stmt) {
-                Ok(Monkey {
-                    num: n,
-                    items,
-                    inspected_items: 0,
-                    statement: stmt,
-                    throw_test: ThrowTest {
-                        modulus: div,
-                        on_true: m_true,
-                        on_false: m_false,
-                    },
+        for (i, line) in behavior.iter().enumerate() {
+            let trimmed_line = line.trim();
+            let parsed = if trimmed_line.is_empty() {
+                Ok(())
+            } else if trimmed_line.starts_with("Monkey") {
+                Monkey::_parse_num_label("Monkey", line).map(|n| num = Some(n))
+            } else if trimmed_line.starts_with("Starting items") {
+                Monkey::_parse_items(line).map(|parsed_items| items = Some(parsed_items))
+            } else if trimmed_line.starts_with("Operation") {
+                Monkey::_parse_operation(line).map(|parsed_stmt| stmt = Some(parsed_stmt))
+            } else if trimmed_line.starts_with("Test") {
+                Monkey::_parse_num_label("by", line).and_then(|modulus| {
+                    if modulus == 0 {
+                        Err(AocError::parse(
+                            Day11::DAY,
+                            line,
+                            "Can't test divisibility by zero.",
+                        ))
+                    } else {
+                        test_modulus = Some(modulus);
+                        Ok(())
+                    }
                 })
+            } else if trimmed_line.starts_with("If true") {
+                Monkey::_parse_target(line, thrower).map(|n| monkey_true = Some(n))
+            } else if trimmed_line.starts_with("If false") {
+                Monkey::_parse_target(line, thrower).map(|n| monkey_false = Some(n))
             } else {
-                Err(ParserError {
-                    reason: "Missing monkey num, items, or behavior operation.".to_string(),
-                })
-            }
-        } else {
-            Err(ParserError {
-                reason: "Missing test modulus, true monkey, or false monkey from monkey behavior description.".to_string()
-            })
+                Err(AocError::parse_at(
+                    Day11::DAY,
+                    line,
+                    trimmed_line,
+                    "Expected part of a monkey's behavior.",
+                ))
+            };
+            parsed.map_err(|err| err.offset_lines(i))?;
         }
+
+        let missing = |description: &str| {
+            AocError::parse(
+                Day11::DAY,
+                behavior.first().copied().unwrap_or_default(),
+                format!("Monkey is missing {description}."),
+            )
+        };
+        Ok(Monkey {
+            num: num.ok_or_else(|| missing("its number"))?,
+            items: items.ok_or_else(|| missing("its starting items"))?,
+            inspected_items: 0,
+            statement: stmt.ok_or_else(|| missing("an operation"))?,
+            throw_test: ThrowTest {
+                modulus: test_modulus.ok_or_else(|| missing("a test"))?,
+                on_true: monkey_true.ok_or_else(|| missing("a monkey to throw to if true"))?,
+                on_false: monkey_false.ok_or_else(|| missing("a monkey to throw to if false"))?,
+            },
+        })
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
     }
 }
 
+/// Least common multiple of two numbers, unless it's too large for a `usize`.
+fn lcm(a: usize, b: usize) -> Option<usize> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
 #[derive(Debug)]
-struct Barrel {
+pub struct Barrel {
     monkeys: Vec<Rc<RefCell<Monkey>>>,
-    modulus_multiple: usize,
+    // None if the multiple is too large for a `usize`.
+    modulus_multiple: Option<usize>,
+    // Whether worry levels are divided by 3 after each inspection.
+    relief: bool,
 }
 
 impl Barrel {
-    fn new(fname: &str) -> Result<Self, Box<dyn Error>> {
-        let contents = fs::read_to_string(fname)?;
+    pub fn new(fname: &str, relief: bool) -> Result<Self, AocError> {
+        Barrel::from_reader(File::open(fname)?, relief)
+    }
+
+    pub fn from_reader(reader: impl Read, relief: bool) -> Result<Self, AocError> {
+        Ok(Barrel::from_monkeys(read_monkeys(reader)?, relief))
+    }
+
+    pub fn from_monkeys(all_monkeys: Vec<Monkey>, relief: bool) -> Self {
         let mut monkeys: Vec<Rc<RefCell<Monkey>>> = vec![];
         let mut moduli: Vec<usize> = vec![];
-        for monkey_behavior in contents.split("\n\r\n") {
-            let monkey = Rc::new(RefCell::new(Monkey::new(monkey_behavior)?));
-            moduli.push(monkey.borrow().throw_test.modulus);
-            monkeys.push(monkey)
+        for monkey in all_monkeys.into_iter() {
+            moduli.push(monkey.throw_test.modulus);
+            monkeys.push(Rc::new(RefCell::new(monkey)))
         }
 
         // Calculate a multiple to use modular arithmetic on the worry level of an item so that the size of item remains manageable.
-        Ok(Barrel {
+        // The least common multiple stays small when monkeys share a modulus, unlike the product.
+        Barrel {
             monkeys,
-            modulus_multiple: moduli.iter().product(),
-        })
+            modulus_multiple: moduli.into_iter().try_fold(1, lcm),
+            relief,
+        }
+    }
+
+    pub fn monkeys(&self) -> Vec<Monkey> {
+        self.monkeys
+            .iter()
+            .map(|monkey| monkey.borrow().clone())
+            .collect_vec()
     }
-    fn start_round(&mut self) {
+    pub fn start_round(&mut self) -> Result<(), AocError> {
         for monkey in self.monkeys.iter() {
-            let mut monkey = monkey.borrow_mut();
-            let mut items_inspected = 0;
-            for item in monkey.items.iter() {
-                // Worry level increased as monkey looks at item. Factor monkey's statement into worry level for item.
-                let new_item = monkey.statement.result(Some(*item)).unwrap();
-
-                // //  Not damaged so divide worry level by 3.
-                // let worry_lvl = new_item / 3;
-
-                // Part 2. Doesn't decrease.
-                // Take the mod of the item's worry level against the product of all the monkey moduli.
-                // We do this so that:
-                //     * Our worry test will still work to select the next monkey.
-                //     * We reduce the size of the worry levels of items so our unsigned values don't overflow.
-                //     * See https://www.reddit.com/r/adventofcode/comments/zih7gf/2022_day_11_part_2_what_does_it_mean_find_another/j02eicp/.
-                let worry_lvl = new_item % self.modulus_multiple;
-
-                let next_monkey_idx = if worry_lvl % monkey.throw_test.modulus == 0 {
-                    monkey.throw_test.on_true
-                } else {
-                    monkey.throw_test.on_false
-                };
-                // Pass item with new worry level to next monkey.
-                if let Some(next_monkey) = self.monkeys.get(next_monkey_idx) {
-                    next_monkey.borrow_mut().items.push(worry_lvl)
-                }
-                // Increment the number of inspected items.
-                items_inspected += 1;
+            // Release the thrower before passing items, which may be to itself.
+            let thrown_items = {
+                let mut monkey = monkey.borrow_mut();
+                let thrown_items = monkey
+                    .items
+                    .iter()
+                    .map(|item| {
+                        // Worry level increased as monkey looks at item. Factor monkey's statement into worry level for item.
+                        let new_item = monkey.statement.result(*item).ok_or_else(|| {
+                            AocError::unsolvable(
+                                Day11::DAY,
+                                format!(
+                                    "Worry level of item {item} is too large or divided by zero after monkey {}'s operation.",
+                                    monkey.num
+                                ),
+                            )
+                        })?;
+
+                        let worry_lvl = if self.relief {
+                            // Not damaged so divide worry level by 3.
+                            new_item / 3
+                        } else {
+                            // Part 2. Doesn't decrease.
+                            // Take the mod of the item's worry level against the least common multiple of all the monkey moduli.
+                            // We do this so that:
+                            //     * Our worry test will still work to select the next monkey.
+                            //     * We reduce the size of the worry levels of items so our unsigned values don't overflow.
+                            //     * See https://www.reddit.com/r/adventofcode/comments/zih7gf/2022_day_11_part_2_what_does_it_mean_find_another/j02eicp/.
+                            let modulus_multiple = self.modulus_multiple.ok_or_else(|| {
+                                AocError::unsolvable(
+                                    Day11::DAY,
+                                    "Least common multiple of the monkeys' tests is too large.",
+                                )
+                            })?;
+                            new_item % modulus_multiple
+                        };
+
+                        let next_monkey_idx = if worry_lvl.is_multiple_of(monkey.throw_test.modulus)
+                        {
+                            monkey.throw_test.on_true
+                        } else {
+                            monkey.throw_test.on_false
+                        };
+                        if next_monkey_idx >= self.monkeys.len() {
+                            return Err(AocError::unsolvable(
+                                Day11::DAY,
+                                format!(
+                                    "Monkey {} throws to monkey {next_monkey_idx}, which doesn't exist.",
+                                    monkey.num
+                                ),
+                            ));
+                        }
+                        Ok((next_monkey_idx, worry_lvl))
+                    })
+                    .collect::<Result<Vec<_>, AocError>>()?;
+                // Update monkey's inspected items and remove the items it threw.
+                monkey.inspected_items += thrown_items.len();
+                monkey.items.clear();
+                thrown_items
+            };
+            // Pass items with new worry levels to the next monkeys.
+            for (next_monkey_idx, worry_lvl) in thrown_items {
+                self.monkeys[next_monkey_idx]
+                    .borrow_mut()
+                    .items
+                    .push(worry_lvl)
             }
-            // Update monkey's inspected items.
-            monkey.inspected_items += items_inspected;
-            // After items moved to next item, remove items.
-            monkey.items.clear()
         }
+        Ok(())
     }
-    fn monkey_business(&mut self, n_rounds: usize) -> Result<usize, &'static str> {
+    pub fn monkey_business(&mut self, n_rounds: usize) -> Result<usize, AocError> {
         // Do n rounds of monkey business.
         for _ in 0..n_rounds {
-            self.start_round();
+            self.start_round()?;
         }
         let n_inspected_items = self
             .monkeys
@@ -287,16 +443,138 @@ impl Barrel {
             .rev()
             .collect_vec();
 
-        if let Some(top_two) = n_inspected_items.get(0..2) {
-            Ok(top_two.iter().product())
+        if let Some(&[first, second]) = n_inspected_items.get(0..2) {
+            first
+                .checked_mul(second)
+                .ok_or_else(|| AocError::unsolvable(Day11::DAY, "Monkey business is too large."))
         } else {
-            Err("Not enough monkeys.")
+            Err(AocError::unsolvable(
+                Day11::DAY,
+                "Need at least two monkeys.",
+            ))
         }
     }
 }
-pub fn monkey_business(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let mut barrel_of_monkeys = Barrel::new(fname)?;
+pub fn parse_monkeys(contents: &str) -> Result<Vec<Monkey>, AocError> {
+    let blocks = input::blocks(contents);
+    let mut monkeys = vec![];
+    for (position, block) in blocks.iter().enumerate() {
+        monkeys.push(
+            Monkey::_parse_behavior(&block.lines, Some((position, blocks.len())))
+                .map_err(|err| err.offset_lines(block.start))?,
+        );
+    }
+    Ok(monkeys)
+}
+
+pub fn read_monkeys(reader: impl Read) -> Result<Vec<Monkey>, AocError> {
+    parse_monkeys(&read_input(reader)?)
+}
+
+pub fn monkey_business(fname: &str) -> Result<usize, AocError> {
+    let mut barrel_of_monkeys = Barrel::new(fname, false)?;
 
     let monke_biz = barrel_of_monkeys.monkey_business(10000)?;
     Ok(monke_biz)
 }
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    type Input = Vec<Monkey>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_monkeys(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        let mut barrel_of_monkeys = Barrel::from_monkeys(input.clone(), true);
+        Ok(barrel_of_monkeys.monkey_business(20)?.into())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        let mut barrel_of_monkeys = Barrel::from_monkeys(input.clone(), false);
+        Ok(barrel_of_monkeys.monkey_business(10000)?.into())
+    }
+}
+
+#[test]
+fn test_throw_targets() {
+    let monkey = |num: usize, on_true: usize, on_false: usize| {
+        format!(
+            "Monkey {num}:\n  Starting items: 79, 98\n  Operation: new = old * 19\n  Test: divisible by 23\n    If true: throw to monkey {on_true}\n    If false: throw to monkey {on_false}\n"
+        )
+    };
+
+    let self_target = [monkey(0, 1, 0), monkey(1, 0, 0)].join("\n");
+    assert_eq!(
+        parse_monkeys(&self_target).unwrap_err().to_string(),
+        "Day 11, line 6, column 31: A monkey can't throw to itself. Found '0'."
+    );
+    let out_of_range = [monkey(0, 1, 1), monkey(1, 2, 0)].join("\n");
+    assert_eq!(
+        parse_monkeys(&out_of_range).unwrap_err().to_string(),
+        "Day 11, line 12, column 30: Expected one of the 2 monkeys. Found '2'."
+    );
+
+    // A monkey built on its own may still throw to itself without a round panicking.
+    let monkeys = [monkey(0, 0, 0), monkey(1, 0, 0)]
+        .iter()
+        .map(|behavior| Monkey::new(behavior).unwrap())
+        .collect_vec();
+    let mut barrel = Barrel::from_monkeys(monkeys, true);
+    barrel.start_round().unwrap();
+    assert_eq!(
+        barrel
+            .monkeys()
+            .iter()
+            .map(Monkey::inspected_items)
+            .collect_vec(),
+        [2, 2]
+    );
+
+    // Or throw to a monkey that doesn't exist, which keeps its items rather than losing them.
+    let monkeys = [monkey(0, 1, 1), monkey(1, 2, 2)]
+        .iter()
+        .map(|behavior| Monkey::new(behavior).unwrap())
+        .collect_vec();
+    let mut barrel = Barrel::from_monkeys(monkeys, true);
+    assert_eq!(
+        barrel.start_round().unwrap_err().to_string(),
+        "Day 11: Monkey 1 throws to monkey 2, which doesn't exist."
+    );
+    assert_eq!(barrel.monkeys()[1].items(), [79, 98, 500, 620]);
+}
+
+#[test]
+fn test_worry_overflow() {
+    let monkeys = parse_monkeys(&format!(
+        "Monkey 0:\n  Starting items: {}\n  Operation: new = old * old\n  Test: divisible by 2\n    If true: throw to monkey 1\n    If false: throw to monkey 1\n\n\
+         Monkey 1:\n  Starting items: 1\n  Operation: new = old + 1\n  Test: divisible by 3\n    If true: throw to monkey 0\n    If false: throw to monkey 0\n",
+        u32::MAX as usize + 1
+    ))
+    .unwrap();
+    assert!(matches!(
+        Barrel::from_monkeys(monkeys, true).monkey_business(1),
+        Err(AocError::Unsolvable { day: 11, .. })
+    ));
+    assert_eq!(lcm(usize::MAX, usize::MAX - 1), None);
+}
+
+#[test]
+fn test_worry_divide_by_zero() {
+    for operation in ["old / old", "3 / old"] {
+        let monkeys = parse_monkeys(&format!(
+            "Monkey 0:\n  Starting items: 0\n  Operation: new = {operation}\n  Test: divisible by 2\n    If true: throw to monkey 1\n    If false: throw to monkey 1\n\n\
+             Monkey 1:\n  Starting items: 1\n  Operation: new = old + 1\n  Test: divisible by 3\n    If true: throw to monkey 0\n    If false: throw to monkey 0\n"
+        ))
+        .unwrap();
+        for relief in [true, false] {
+            assert!(matches!(
+                Barrel::from_monkeys(monkeys.clone(), relief).monkey_business(1),
+                Err(AocError::Unsolvable { day: 11, .. })
+            ));
+        }
+    }
+}
@@ -1,174 +1,136 @@
-use std::{collections::HashMap, error::Error, fs};
+use std::{fs::File, io::Read};
 
-use itertools::Itertools;
 use pathfinding::prelude::astar;
+use tracing::{debug, trace};
 
-use crate::days::{common::alphabet, common::GridString, error::ParserError};
+use crate::days::{
+    common::{read_input, Grid},
+    error::AocError,
+    point::Point,
+    solution::{Answer, Solution},
+};
 
 const STARTING_POS: char = 'S';
 const ENDING_POS: char = 'E';
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct Pos {
-    row: usize,
-    col: usize,
+/// Elevation of a square. The start is at elevation `a` and the end at elevation `z`.
+fn elevation(square: char) -> u8 {
+    match square {
+        STARTING_POS => b'a',
+        ENDING_POS => b'z',
+        square => square as u8,
+    }
 }
 
 // https://medium.com/@nicholas.w.swift/easy-a-star-pathfinding-7e6689c7f7b2
-impl Pos {
-    fn distance(&self, other: &Pos) -> u32 {
-        (self.row.abs_diff(other.row) + self.col.abs_diff(other.col)) as u32
-    }
-
-    fn map_coord_to_height(
-        row: usize,
-        col: usize,
-        grid: &GridString,
-        alpha_map: &HashMap<char, usize>,
-    ) -> usize {
-        let curr_height_char = grid.get_one(row, col).expect("No character at coord.");
-        *alpha_map
-            .get(&curr_height_char)
-            .expect("Character not in alphabet.")
-    }
+fn distance(pos: &(usize, usize), other: &(usize, usize)) -> u32 {
+    Point::from_grid_index(*pos).manhattan(Point::from_grid_index(*other)) as u32
+}
 
-    fn successors<F: Fn(usize, usize) -> bool>(
-        &self,
-        grid: &GridString,
-        condition: F,
-        alpha_map: &HashMap<char, usize>,
-    ) -> Vec<(Pos, u32)> {
-        let curr_height = Pos::map_coord_to_height(self.row, self.col, grid, alpha_map);
-        // println!("({},{})", self.row, self.col);
-        // Check bounds of adjaceny using Grid.
-        let adjs = [
-            (
-                self.row,
-                (self.col + 1).clamp(0, grid.cols.saturating_sub(1)),
-            ),
-            (self.row, self.col.saturating_sub(1)),
-            (
-                (self.row + 1).clamp(0, grid.rows.saturating_sub(1)),
-                self.col,
-            ),
-            (self.row.saturating_sub(1), self.col),
-        ];
-
-        // Go through adjacencies and calculate the height of the position.
-        // Add as successor only if the absolute difference between the adj pos and curr pos heights is less than or equal to 1.
-        adjs.iter()
-            .filter_map(|(row, col)| {
-                let adj_height = Pos::map_coord_to_height(*row, *col, grid, alpha_map);
-                (condition(adj_height, curr_height)).then(|| {
-                    (
-                        Pos {
-                            row: *row,
-                            col: *col,
-                        },
-                        1,
-                    )
-                })
-            })
-            .collect()
-    }
+/// Squares one step away that are at most one higher than the current square.
+fn successors(heightmap: &Grid<char>, pos: (usize, usize)) -> Vec<((usize, usize), u32)> {
+    let curr_height = elevation(heightmap[pos]);
+    heightmap
+        .neighbours_4(pos)
+        .filter(|adj| elevation(heightmap[*adj]) <= curr_height + 1)
+        .map(|adj| (adj, 1))
+        .collect()
 }
 
-pub fn hill_climb(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
-
-    let mut grid = GridString::new(&contents)?;
-    let alphabet = alphabet();
-
-    let (start_pos, stop_pos) = (
-        grid.search(STARTING_POS).ok_or(ParserError {
-            reason: format!("No starting position {STARTING_POS}"),
-        })?,
-        grid.search(ENDING_POS).ok_or(ParserError {
-            reason: format!("No ending position {ENDING_POS}"),
-        })?,
-    );
-
-    // Set starting and ending position elevation.
-    grid.grid = grid
-        .grid
-        .replace(ENDING_POS, "z")
-        .replace(STARTING_POS, "a");
-
-    println!("{:?}\nStart: {:?}, End: {:?}", grid, start_pos, stop_pos);
-    let start_node = Pos {
-        row: start_pos.0,
-        col: start_pos.1,
-    };
-    let stop_node = Pos {
-        row: stop_pos.0,
-        col: stop_pos.1,
-    };
-
-    let (path, n_steps) = astar(
-        &start_node,
-        |p| p.successors(&grid, |adj, curr| adj <= curr + 1, &alphabet),
-        |p| p.distance(&stop_node),
-        |p| *p == stop_node,
+/// Parse a heightmap of elevations from `a` to `z` with a start `S` and an end `E`.
+pub fn parse_heightmap(contents: &str) -> Result<Grid<char>, AocError> {
+    Grid::parse(
+        contents,
+        "Expected an elevation from a to z, S or E.",
+        |square| {
+            (square.is_ascii_lowercase() || [STARTING_POS, ENDING_POS].contains(&square))
+                .then_some(square)
+        },
     )
-    .ok_or(ParserError {
-        reason: format!("No path found."),
-    })?;
+    .map_err(|err| err.with_day(Day12::DAY))
+}
 
-    Ok(n_steps as usize)
+pub fn read_heightmap(reader: impl Read) -> Result<Grid<char>, AocError> {
+    parse_heightmap(&read_input(reader)?)
 }
 
-pub fn hill_climb_any_start(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
+/// Position of the end square.
+fn ending_pos(heightmap: &Grid<char>) -> Result<(usize, usize), AocError> {
+    heightmap
+        .position(|square| *square == ENDING_POS)
+        .ok_or_else(|| {
+            AocError::unsolvable(Day12::DAY, format!("No ending position {ENDING_POS}."))
+        })
+}
 
-    let mut grid = GridString::new(&contents)?;
-    let alphabet = alphabet();
+pub fn fewest_steps(heightmap: &Grid<char>) -> Result<usize, AocError> {
+    let start_pos = heightmap
+        .position(|square| *square == STARTING_POS)
+        .ok_or_else(|| {
+            AocError::unsolvable(Day12::DAY, format!("No starting position {STARTING_POS}."))
+        })?;
+    let stop_pos = ending_pos(heightmap)?;
+
+    debug!("Start: {start_pos:?}, End: {stop_pos:?}");
+    let (_, n_steps) = astar(
+        &start_pos,
+        |p| successors(heightmap, *p),
+        |p| distance(p, &stop_pos),
+        |p| *p == stop_pos,
+    )
+    .ok_or_else(|| AocError::unsolvable(Day12::DAY, "No path found."))?;
 
-    // Set starting and ending position elevation.
-    grid.grid = grid
-        .grid
-        .replace(ENDING_POS, "z")
-        .replace(STARTING_POS, "a");
+    Ok(n_steps as usize)
+}
 
-    let (all_start_pos, stop_pos) = (
-        grid.search_all('a').ok_or(ParserError {
-            reason: format!("No starting position 'a'"),
-        })?,
-        grid.search('z').ok_or(ParserError {
-            reason: format!("No ending position 'z'"),
-        })?,
-    );
+pub fn fewest_steps_any_start(heightmap: &Grid<char>) -> Result<usize, AocError> {
+    let stop_pos = ending_pos(heightmap)?;
 
     let mut all_paths_n_steps = vec![];
-    for start_pos in all_start_pos {
-        println!("Start: {:?}, End: {:?}", start_pos, stop_pos);
-        let start_node = Pos {
-            row: start_pos.0,
-            col: start_pos.1,
-        };
-        let stop_node = Pos {
-            row: stop_pos.0,
-            col: stop_pos.1,
-        };
-
+    for start_pos in heightmap.positions(|square| elevation(*square) == b'a') {
+        trace!("Start: {start_pos:?}, End: {stop_pos:?}");
         if let Some((_, n_steps)) = astar(
-            &start_node,
-            |p| p.successors(&grid, |adj, curr| adj <= curr + 1, &alphabet),
-            |p| p.distance(&stop_node),
-            |p| *p == stop_node,
+            &start_pos,
+            |p| successors(heightmap, *p),
+            |p| distance(p, &stop_pos),
+            |p| *p == stop_pos,
         ) {
             all_paths_n_steps.push(n_steps)
-        } else {
-            continue;
         }
     }
 
-    println!("{:?}", all_paths_n_steps);
+    debug!("Steps from each start: {all_paths_n_steps:?}");
     if let Some(least_n_steps) = all_paths_n_steps.iter().min() {
         Ok(*least_n_steps as usize)
     } else {
-        Err(Box::new(ParserError {
-            reason: "No paths found.".to_string(),
-        }))
+        Err(AocError::unsolvable(Day12::DAY, "No paths found."))
+    }
+}
+
+pub fn hill_climb(fname: &str) -> Result<usize, AocError> {
+    fewest_steps(&read_heightmap(File::open(fname)?)?)
+}
+
+pub fn hill_climb_any_start(fname: &str) -> Result<usize, AocError> {
+    fewest_steps_any_start(&read_heightmap(File::open(fname)?)?)
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_heightmap(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(fewest_steps(input)?.into())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(fewest_steps_any_start(input)?.into())
     }
 }
 
@@ -1,10 +1,22 @@
-use std::borrow::Borrow;
 use std::cell::RefCell;
-use std::error::Error;
-use std::fs;
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::Read;
 use std::rc::{Rc, Weak};
 
 use itertools::Itertools;
+use tracing::trace;
+
+use crate::days::{
+    common::read_input,
+    error::AocError,
+    input,
+    solution::{Answer, Solution},
+};
+
+/// Most lists that may be nested inside a packet. Comparing, printing and dropping packets
+/// recurse through nested lists, so deeper packets are rejected rather than overflowing the stack.
+pub const MAX_PACKET_DEPTH: usize = 256;
 
 #[derive(Debug, Clone)]
 enum Value {
@@ -13,74 +25,64 @@ enum Value {
 }
 
 impl Value {
-    fn in_order(left: &Value, right: &Value) -> bool {
+    /// Compare two values.
+    /// * Items are compared by value.
+    /// * Lists are compared item by item and then by length.
+    /// * An item compared to a list is first converted to a list with the item as its only value.
+    fn compare(left: &Value, right: &Value) -> Ordering {
         match (left, right) {
             (Value::Item(left_single_value), Value::Item(right_single_value)) => {
-                // Not in order because left side value is larger than right side value.
-                left_single_value <= right_single_value
+                left_single_value.cmp(right_single_value)
             }
             /*
             [1,[2,[3,[4,[5,6,0]]]],8,9]
             [[2,[3,[4,[5,6,7]]]],8,9, 1]
             */
             (Value::Item(_), Value::List(right_list_value)) => {
-                // Recurse through in case multiple first items are Lists.
-                // We need a single value for comparison.
-                if let Some(inner_r) = right_list_value.items.borrow().get(0) {
-                    Value::in_order(left, inner_r)
-                } else {
-                    // Left side has value so not in order.
-                    false
-                }
+                Packet::compare_items(&[Rc::new(left.clone())], &right_list_value.items.borrow())
             }
             (Value::List(left_list_value), Value::Item(_)) => {
-                // Recurse through in case multiple first items are Lists.
-                // We need a single value for comparison.
-                if let Some(inner_l) = left_list_value.items.borrow().get(0) {
-                    Value::in_order(inner_l, right)
-                } else {
-                    // Right side has value so in order.
-                    true
-                }
+                Packet::compare_items(&left_list_value.items.borrow(), &[Rc::new(right.clone())])
             }
             (Value::List(left_list_value), Value::List(right_list_value)) => {
-                for inner_items in left_list_value
-                    .items
-                    .borrow()
-                    .iter()
-                    .zip_longest(right_list_value.items.borrow().iter())
-                {
-                    match inner_items {
-                        itertools::EitherOrBoth::Both(inner_l, inner_r) => {
-                            if !Value::in_order(inner_l, inner_r) {
-                                return false;
-                            }
-                        }
-                        itertools::EitherOrBoth::Left(_) => {
-                            return false;
-                        }
-                        itertools::EitherOrBoth::Right(_) => {
-                            return true;
-                        }
-                    }
-                }
-
-                true
+                Packet::compare(left_list_value, right_list_value)
             }
         }
     }
 }
 
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Item(value) => write!(f, "{value}"),
+            Value::List(packet) => write!(f, "{packet}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
-struct Packet {
+pub struct Packet {
     parent: Option<Weak<Packet>>,
     items: RefCell<Vec<Rc<Value>>>,
 }
 
 impl Packet {
-    fn new(str_list: &str) -> Rc<Packet> {
+    /// Parse a packet from a list like `[1,[2,3],4]`.
+    pub fn new(str_list: &str) -> Result<Rc<Packet>, AocError> {
+        let packet_err =
+            |token: &str, reason: &str| AocError::parse_at(Day13::DAY, str_list, token, reason);
+
         // Ignore 1st and last character. [...]
-        let str_list = str_list.get(1..(str_list.len() - 1)).unwrap();
+        let trimmed_list = str_list.trim();
+        let Some(items) = trimmed_list
+            .strip_prefix('[')
+            .and_then(|list| list.strip_suffix(']'))
+        else {
+            return Err(packet_err(
+                trimmed_list,
+                "Expected a list enclosed in '[' and ']'.",
+            ));
+        };
 
         // Set root of list of packets.
         let root = Rc::new(Packet {
@@ -89,9 +91,38 @@ impl Packet {
         });
 
         let current: RefCell<Rc<Packet>> = RefCell::new(root.clone());
-        for c in str_list.chars() {
+        // Start of the item currently being read.
+        let mut item_start: Option<usize> = None;
+        // Lists open inside the packet.
+        let mut depth = 0;
+        // Previous character, which decides what may come next.
+        let mut prev = '[';
+        for (i, c) in items.char_indices() {
+            let token = &items[i..i + c.len_utf8()];
+            // Items are separated by single commas, and lists may be empty.
+            let after_item = prev.is_ascii_digit() || prev == ']';
+            if c == ',' && !after_item {
+                return Err(packet_err(token, "Expected an item before ','."));
+            }
+            if c == ']' && prev == ',' {
+                return Err(packet_err(token, "Expected an item after ','."));
+            }
+            if (c == '[' || c.is_ascii_digit())
+                && after_item
+                && !(prev.is_ascii_digit() && c.is_ascii_digit())
+            {
+                return Err(packet_err(token, "Expected ',' between items."));
+            }
+            prev = c;
             match c {
                 '[' => {
+                    depth += 1;
+                    if depth > MAX_PACKET_DEPTH {
+                        return Err(packet_err(
+                            token,
+                            &format!("Lists are nested more than {MAX_PACKET_DEPTH} deep."),
+                        ));
+                    }
                     let child = Rc::new(Packet {
                         parent: Some(Rc::downgrade(&*current.borrow())),
                         items: RefCell::new(vec![]),
@@ -105,82 +136,164 @@ impl Packet {
                     // Set child to current.
                     *current.borrow_mut() = child;
                 }
-                ',' => {}
-                ']' => {
-                    let parent_packet = current.borrow().parent.clone();
-                    if let Some(Some(parent_packet)) = parent_packet.map(|parent| parent.upgrade())
-                    {
-                        *current.borrow_mut() = parent_packet.clone()
-                    } else {
-                        println!("Can't get parent.")
+                ',' | ']' => {
+                    if let Some(start) = item_start.take() {
+                        current.borrow().push_item(str_list, &items[start..i])?;
                     }
-                }
-                _ => {
-                    if let Some(parsed_c) = c.to_digit(10) {
-                        current
-                            .borrow()
-                            .items
-                            .borrow_mut()
-                            .push(Rc::new(Value::Item(parsed_c)))
-                    } else {
-                        println!("Can't parse {}", c)
+                    if c == ']' {
+                        let parent_packet = current.borrow().parent.clone();
+                        let Some(parent_packet) = parent_packet.and_then(|parent| parent.upgrade())
+                        else {
+                            return Err(packet_err(token, "Unmatched ']'."));
+                        };
+                        depth -= 1;
+                        *current.borrow_mut() = parent_packet
                     }
                 }
+                '0'..='9' => {
+                    item_start.get_or_insert(i);
+                }
+                _ => return Err(packet_err(token, "Expected a digit, ',', '[' or ']'.")),
             }
         }
+        if prev == ',' {
+            return Err(packet_err(
+                &trimmed_list[trimmed_list.len() - 1..],
+                "Expected an item after ','.",
+            ));
+        }
+        if let Some(start) = item_start {
+            current.borrow().push_item(str_list, &items[start..])?;
+        }
+        if !Rc::ptr_eq(&current.borrow(), &root) {
+            return Err(packet_err(trimmed_list, "Unclosed list."));
+        }
 
-        root
+        Ok(root)
     }
 
-    fn get(&self, n: usize) -> Option<Rc<Value>> {
-        self.items.borrow().get(n).cloned()
+    /// Add an item, a slice of the packet's `line`, to the packet.
+    fn push_item(&self, line: &str, item: &str) -> Result<(), AocError> {
+        let value = item
+            .parse::<u32>()
+            .map_err(|_| AocError::parse_at(Day13::DAY, line, item, "Item is too large."))?;
+        self.items.borrow_mut().push(Rc::new(Value::Item(value)));
+        Ok(())
     }
-}
-
-pub fn distress_signal(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
 
-    let mut in_order_idx: Vec<usize> = vec![];
+    pub fn compare(left: &Packet, right: &Packet) -> Ordering {
+        Packet::compare_items(&left.items.borrow(), &right.items.borrow())
+    }
 
-    'group_loop: for (i, packet_grp) in contents.split("\n\r\n").enumerate() {
-        println!("Group: {i}");
-        if let Some((packet_1, packet_2)) = packet_grp
-            .lines()
-            .map(|packet| Packet::new(packet))
-            .collect_tuple::<(Rc<Packet>, Rc<Packet>)>()
-        {
-            // println!("{:?}", packet_1);
-            // println!("{:?}", packet_2);
-            for item in packet_1
-                .items
-                .borrow()
-                .iter()
-                .zip_longest(packet_2.items.borrow().iter())
-            {
-                match item {
-                    itertools::EitherOrBoth::Both(left_item, right_item) => {
-                        println!("\tLeft: {:?} - Right {:?}", left_item, right_item);
-                        if !Value::in_order(left_item, right_item) {
-                            continue 'group_loop;
-                        }
-                    }
-                    itertools::EitherOrBoth::Left(_) => {
-                        // Not in right order. Correct if left has less than right in # of items.
-                        continue 'group_loop;
-                    }
-                    itertools::EitherOrBoth::Right(_) => {
-                        println!("\tLeft ran out of items.");
-                        in_order_idx.push(i + 1);
-                        continue 'group_loop;
+    fn compare_items(left: &[Rc<Value>], right: &[Rc<Value>]) -> Ordering {
+        for inner_items in left.iter().zip_longest(right.iter()) {
+            match inner_items {
+                itertools::EitherOrBoth::Both(inner_l, inner_r) => {
+                    match Value::compare(inner_l, inner_r) {
+                        Ordering::Equal => continue,
+                        ordering => return ordering,
                     }
                 }
+                // Right side ran out of items first.
+                itertools::EitherOrBoth::Left(_) => return Ordering::Greater,
+                // Left side ran out of items first.
+                itertools::EitherOrBoth::Right(_) => return Ordering::Less,
             }
-            // If no issue in comparisons and doesn't continue early, is in order.
+        }
+        Ordering::Equal
+    }
+}
+
+impl std::fmt::Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}]", self.items.borrow().iter().join(","))
+    }
+}
+
+/// Left and right packets to compare.
+pub type PacketPair = (Rc<Packet>, Rc<Packet>);
+
+/// Parse pairs of packets. Each pair is separated from the next by a blank line.
+pub fn parse_packet_pairs(contents: &str) -> Result<Vec<PacketPair>, AocError> {
+    let mut packet_pairs = vec![];
+
+    for block in input::blocks(contents) {
+        let packets = block
+            .numbered_lines()
+            .map(|(i, line)| Packet::new(line).map_err(|err| err.offset_lines(i)))
+            .collect::<Result<Vec<Rc<Packet>>, AocError>>()?;
+        let Some(pair) = packets.into_iter().collect_tuple::<PacketPair>() else {
+            return Err(AocError::parse(
+                Day13::DAY,
+                block.lines[0],
+                "Expected a pair of packets followed by a blank line.",
+            )
+            .offset_lines(block.start));
+        };
+        packet_pairs.push(pair)
+    }
+    Ok(packet_pairs)
+}
+
+pub fn sum_ordered_pairs(packet_pairs: &[PacketPair]) -> usize {
+    let mut in_order_idx: Vec<usize> = vec![];
+
+    for (i, (packet_1, packet_2)) in packet_pairs.iter().enumerate() {
+        trace!("Group: {i}\n\tLeft: {packet_1} - Right {packet_2}");
+        if Packet::compare(packet_1, packet_2) != Ordering::Greater {
             in_order_idx.push(i + 1);
         }
     }
 
-    Ok(in_order_idx.iter().sum())
+    in_order_idx.iter().sum()
+}
+
+pub fn decoder_key(packet_pairs: &[PacketPair]) -> Result<usize, AocError> {
+    let dividers = [Packet::new("[[2]]")?, Packet::new("[[6]]")?];
+    let sorted_packets = packet_pairs
+        .iter()
+        .flat_map(|(packet_1, packet_2)| [packet_1, packet_2])
+        .chain(dividers.iter())
+        .sorted_by(|packet_1, packet_2| Packet::compare(packet_1, packet_2))
+        .collect_vec();
+
+    // Dividers are always present in the sorted packets.
+    Ok(dividers
+        .iter()
+        .filter_map(|divider| {
+            sorted_packets
+                .iter()
+                .position(|packet| Rc::ptr_eq(packet, divider))
+        })
+        .map(|idx| idx + 1)
+        .product())
+}
+
+pub fn read_packet_pairs(reader: impl Read) -> Result<Vec<PacketPair>, AocError> {
+    parse_packet_pairs(&read_input(reader)?)
+}
+
+pub fn distress_signal(fname: &str) -> Result<usize, AocError> {
+    Ok(sum_ordered_pairs(&read_packet_pairs(File::open(fname)?)?))
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    type Input = Vec<PacketPair>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_packet_pairs(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(sum_ordered_pairs(input).into())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(decoder_key(input)?.into())
+    }
 }
 
 #[test]
@@ -190,3 +303,31 @@ fn test_distress_signal() {
     let res = distress_signal(test_file).unwrap();
     assert_eq!(res, exp_sum_ord_indices);
 }
+
+#[test]
+fn test_malformed_packets() {
+    for (packet, reason) in [
+        ("[1,,2]", "Expected an item before ','."),
+        ("[,]", "Expected an item before ','."),
+        ("[1,]", "Expected an item after ','."),
+        ("[[1,]]", "Expected an item after ','."),
+        ("[1[2]]", "Expected ',' between items."),
+        ("[[1]2]", "Expected ',' between items."),
+    ] {
+        match Packet::new(packet) {
+            Err(AocError::Parse { reason: err, .. }) => assert_eq!(err, reason, "{packet}"),
+            parsed => panic!("{packet} parsed as {parsed:?}"),
+        }
+    }
+    assert_eq!(
+        Packet::new("[[],[1,[]],10]").unwrap().to_string(),
+        "[[],[1,[]],10]"
+    );
+
+    let nested = |depth: usize| format!("[{}{}]", "[".repeat(depth), "]".repeat(depth));
+    let deepest = Packet::new(&nested(MAX_PACKET_DEPTH)).unwrap();
+    assert_eq!(Packet::compare(&deepest, &deepest), Ordering::Equal);
+    assert_eq!(deepest.to_string(), nested(MAX_PACKET_DEPTH));
+    assert!(Packet::new(&nested(MAX_PACKET_DEPTH + 1)).is_err());
+    assert!(Packet::new(&nested(100_000)).is_err());
+}
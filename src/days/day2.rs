@@ -1,175 +1,476 @@
 use itertools::Itertools;
-use std::cmp::Ordering::{Equal, Greater, Less};
-use std::error::Error;
-use std::fs;
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum Move {
+use tracing::trace;
+
+use crate::days::{
+    common::read_input,
+    error::AocError,
+    input,
+    solution::{Answer, Solution},
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Move {
     Rock = 1,
     Paper = 2,
     Scissor = 3,
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum Outcome {
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Outcome {
     Win = 6,
     Draw = 3,
     Loss = 0,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseRPSError;
-
-impl Error for ParseRPSError {}
-
-impl std::fmt::Display for ParseRPSError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Unable to parse string to RPS move.")
-    }
-}
 impl FromStr for Move {
-    type Err = ParseRPSError;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.trim() {
             "A" | "X" => Ok(Move::Rock),
             "B" | "Y" => Ok(Move::Paper),
             "C" | "Z" => Ok(Move::Scissor),
-            _ => Err(ParseRPSError),
+            _ => Err(AocError::parse(
+                Day2::DAY,
+                s,
+                "Expected a move (A, B, C, X, Y or Z).",
+            )),
         }
     }
 }
 
 impl FromStr for Outcome {
-    type Err = ParseRPSError;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.trim() {
             "X" => Ok(Outcome::Loss),
             "Y" => Ok(Outcome::Draw),
             "Z" => Ok(Outcome::Win),
-            _ => Err(ParseRPSError),
+            _ => Err(AocError::parse(
+                Day2::DAY,
+                s,
+                "Expected an outcome (X, Y or Z).",
+            )),
         }
     }
 }
 
-impl std::cmp::PartialOrd for Move {
-    fn lt(&self, other: &Self) -> bool {
-        matches!(self.partial_cmp(other), Some(Less))
+impl Move {
+    /// Position of the move among the shapes of [`Game::rps`].
+    pub fn shape(self) -> usize {
+        self as usize - 1
     }
 
-    fn le(&self, other: &Self) -> bool {
-        matches!(self.partial_cmp(other), Some(Less | Equal))
+    /// Move against which this move ends with `outcome`.
+    pub fn get_outcome(&self, outcome: &Outcome) -> Option<Move> {
+        let yours = match outcome {
+            Outcome::Win => Outcome::Loss,
+            Outcome::Draw => Outcome::Draw,
+            Outcome::Loss => Outcome::Win,
+        };
+        let shape = Game::rps().shape_for(self.shape(), yours)?;
+        [Move::Rock, Move::Paper, Move::Scissor]
+            .into_iter()
+            .find(|mv| mv.shape() == shape)
     }
+}
+
+/// Scores of ending a round with each outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutcomeScores {
+    pub loss: usize,
+    pub draw: usize,
+    pub win: usize,
+}
+
+impl OutcomeScores {
+    /// 0 for a loss, 3 for a draw and 6 for a win.
+    pub const STANDARD: OutcomeScores = OutcomeScores {
+        loss: 0,
+        draw: 3,
+        win: 6,
+    };
 
-    fn gt(&self, other: &Self) -> bool {
-        matches!(self.partial_cmp(other), Some(Greater))
+    pub fn get(&self, outcome: Outcome) -> usize {
+        match outcome {
+            Outcome::Loss => self.loss,
+            Outcome::Draw => self.draw,
+            Outcome::Win => self.win,
+        }
     }
+}
+
+/// Cyclic game of an odd number of shapes, numbered from 0. Each shape beats the half of the
+/// other shapes just before it, wrapping around, and loses to the half just after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    shape_scores: Vec<usize>,
+    outcome_scores: OutcomeScores,
+}
 
-    fn ge(&self, other: &Self) -> bool {
-        matches!(self.partial_cmp(other), Some(Greater | Equal))
+impl Game {
+    /// Game scoring each shape by `shape_scores`. `None` unless there's an odd number of shapes.
+    pub fn new(shape_scores: Vec<usize>, outcome_scores: OutcomeScores) -> Option<Self> {
+        (shape_scores.len() % 2 == 1).then_some(Game {
+            shape_scores,
+            outcome_scores,
+        })
     }
 
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match self {
-            Move::Rock => match other {
-                Move::Rock => Some(Equal),
-                Move::Paper => Some(Less),
-                Move::Scissor => Some(Greater),
-            },
-            Move::Paper => match other {
-                Move::Rock => Some(Greater),
-                Move::Paper => Some(Equal),
-                Move::Scissor => Some(Less),
-            },
-            Move::Scissor => match other {
-                Move::Rock => Some(Less),
-                Move::Paper => Some(Greater),
-                Move::Scissor => Some(Equal),
-            },
+    /// Rock, paper and scissors, scoring 1 to 3 for the shape plus 0, 3 or 6 for the outcome.
+    pub fn rps() -> Self {
+        Game {
+            shape_scores: vec![1, 2, 3],
+            outcome_scores: OutcomeScores::STANDARD,
         }
     }
+
+    /// Rock, Spock, paper, lizard and scissors, scoring 1 to 5 for the shape plus 0, 3 or 6 for the outcome.
+    pub fn rpsls() -> Self {
+        Game {
+            shape_scores: vec![1, 2, 3, 4, 5],
+            outcome_scores: OutcomeScores::STANDARD,
+        }
+    }
+
+    /// Game of `n_shapes` scoring 1 to `n_shapes` for the shape plus 0, 3 or 6 for the outcome.
+    /// `None` unless there's an odd number of shapes.
+    pub fn with_shapes(n_shapes: usize) -> Option<Self> {
+        Game::new((1..=n_shapes).collect(), OutcomeScores::STANDARD)
+    }
+
+    pub fn n_shapes(&self) -> usize {
+        self.shape_scores.len()
+    }
+
+    /// Outcome for a player of `shape` against an opponent's shape. `None` unless both shapes are in the game.
+    pub fn outcome(&self, shape: usize, opponent: usize) -> Option<Outcome> {
+        let n_shapes = self.n_shapes();
+        if shape >= n_shapes || opponent >= n_shapes {
+            return None;
+        }
+        Some(match (shape + n_shapes - opponent) % n_shapes {
+            0 => Outcome::Draw,
+            ahead if ahead <= n_shapes / 2 => Outcome::Win,
+            _ => Outcome::Loss,
+        })
+    }
+
+    /// Shape that ends with `outcome` against an opponent's shape, if any.
+    pub fn shape_for(&self, opponent: usize, outcome: Outcome) -> Option<usize> {
+        let n_shapes = self.n_shapes();
+        if opponent >= n_shapes {
+            return None;
+        }
+        let shape = match outcome {
+            Outcome::Win => (opponent + 1) % n_shapes,
+            Outcome::Draw => opponent,
+            Outcome::Loss => (opponent + n_shapes - 1) % n_shapes,
+        };
+        (self.outcome(shape, opponent)? == outcome).then_some(shape)
+    }
+
+    /// Score of a player of `shape` against an opponent's shape. `None` unless both shapes are in
+    /// the game and the score fits in a `usize`.
+    pub fn score(&self, shape: usize, opponent: usize) -> Option<usize> {
+        let outcome = self.outcome(shape, opponent)?;
+        self.shape_scores[shape].checked_add(self.outcome_scores.get(outcome))
+    }
 }
 
-impl Move {
-    pub fn get_outcome(&self, outcome: &Outcome) -> Option<Move> {
-        let mut outcome_mv = None;
-        for mv in [Move::Paper, Move::Rock, Move::Scissor] {
-            let res = if self > &mv {
-                Outcome::Win
-            } else if self == &mv {
-                Outcome::Draw
-            } else {
-                Outcome::Loss
-            };
-            if outcome == &res {
-                outcome_mv = Some(mv);
-                break;
-            }
+/// Score of a round of rock, paper and scissors.
+fn rps_score(game: &Game, shape: usize, opponent: usize) -> Result<usize, AocError> {
+    game.score(shape, opponent).ok_or_else(|| {
+        AocError::unsolvable(
+            Day2::DAY,
+            format!("No score for shape {shape} against shape {opponent}."),
+        )
+    })
+}
+
+/// Columns of each round in the strategy guide.
+pub type Guide = Vec<(String, String)>;
+
+/// Parse rounds of an opponent's move (`A`, `B` or `C`) and a response (`X`, `Y` or `Z`).
+pub fn parse_guide(prompt: &str) -> Result<Guide, AocError> {
+    let mut guide = vec![];
+    for (i, line) in input::non_blank_lines(prompt) {
+        let round = line.split_whitespace().collect_vec();
+        let [exp, resp] = round[..] else {
+            return Err(AocError::parse(
+                Day2::DAY,
+                line,
+                "Expected an opponent's move and a response separated by a space.",
+            )
+            .offset_lines(i));
+        };
+        if !["A", "B", "C"].contains(&exp) {
+            return Err(
+                AocError::parse_at(Day2::DAY, line, exp, "Expected A, B or C.").offset_lines(i),
+            );
+        }
+        if !["X", "Y", "Z"].contains(&resp) {
+            return Err(
+                AocError::parse_at(Day2::DAY, line, resp, "Expected X, Y or Z.").offset_lines(i),
+            );
         }
-        outcome_mv
+        guide.push((exp.to_string(), resp.to_string()))
     }
+    Ok(guide)
 }
 
-pub fn rps(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let prompt = fs::read_to_string(fname)?;
+pub fn score_moves(guide: &Guide) -> Result<usize, AocError> {
+    let game = Game::rps();
+    let mut your_score: usize = 0;
+
+    for (i, (exp, resp)) in guide.iter().enumerate() {
+        let opp_move = Move::from_str(exp).map_err(|err| err.offset_lines(i))?;
+        let your_move = Move::from_str(resp).map_err(|err| err.offset_lines(i))?;
+        your_score += rps_score(&game, your_move.shape(), opp_move.shape())?;
+    }
+    Ok(your_score)
+}
 
-    // Init score.
+pub fn score_outcomes(guide: &Guide) -> Result<usize, AocError> {
+    let game = Game::rps();
     let mut your_score: usize = 0;
 
-    for res in prompt.trim().split("\n") {
-        let game_instr = res.split(" ").collect_vec();
-        // R - 1, P - 2, S - 3
-        // W - 6, Draw - 3, L - 0
-        if let (Some(exp), Some(resp)) = (game_instr.get(0), game_instr.get(1)) {
-            let opp_move = Move::from_str(exp)?;
-            let your_move = Move::from_str(resp)?;
-
-            if your_move > opp_move {
-                your_score += 6
-            } else if your_move == opp_move {
-                your_score += 3
+    for (i, (exp, resp)) in guide.iter().enumerate() {
+        let opp_move = Move::from_str(exp).map_err(|err| err.offset_lines(i))?;
+        let your_outcome = Outcome::from_str(resp).map_err(|err| err.offset_lines(i))?;
+        let your_shape = game
+            .shape_for(opp_move.shape(), your_outcome)
+            .ok_or_else(|| {
+                AocError::unsolvable(
+                    Day2::DAY,
+                    format!("No move against {opp_move:?} to {your_outcome:?}."),
+                )
+            })?;
+        let score = rps_score(&game, your_shape, opp_move.shape())?;
+        trace!("{exp} {resp}: {opp_move:?} vs shape {your_shape} scores {score}");
+
+        your_score += score;
+    }
+    Ok(your_score)
+}
+
+/// Meaning of `X`, `Y` and `Z` in the second column of the strategy guide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpretation {
+    /// Moves to play, as in part 1.
+    Moves([Move; 3]),
+    /// Outcomes to aim for, as in part 2.
+    Outcomes([Outcome; 3]),
+}
+
+impl fmt::Display for Interpretation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let meanings = match self {
+            Interpretation::Moves(moves) => moves.map(|mv| format!("{mv:?}")),
+            Interpretation::Outcomes(outcomes) => outcomes.map(|outcome| format!("{outcome:?}")),
+        };
+        write!(
+            f,
+            "X = {}, Y = {}, Z = {}",
+            meanings[0], meanings[1], meanings[2]
+        )
+    }
+}
+
+impl Interpretation {
+    /// Every mapping of `X`, `Y` and `Z` to distinct moves, then to distinct outcomes.
+    pub fn all() -> Vec<Interpretation> {
+        let moves = [Move::Rock, Move::Paper, Move::Scissor]
+            .into_iter()
+            .permutations(3)
+            .map(|moves| Interpretation::Moves([moves[0], moves[1], moves[2]]));
+        let outcomes = [Outcome::Loss, Outcome::Draw, Outcome::Win]
+            .into_iter()
+            .permutations(3)
+            .map(|outcomes| Interpretation::Outcomes([outcomes[0], outcomes[1], outcomes[2]]));
+        moves.chain(outcomes).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoredInterpretation {
+    pub interpretation: Interpretation,
+    pub score: usize,
+}
+
+/// Score of the strategy guide under every interpretation of its second column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuideDecoding {
+    /// Scores in the order of [`Interpretation::all`].
+    pub scores: Vec<ScoredInterpretation>,
+    /// Highest scoring interpretation, the first one on ties.
+    pub best: ScoredInterpretation,
+    /// Lowest scoring interpretation, the first one on ties.
+    pub worst: ScoredInterpretation,
+}
+
+/// Score the guide under each of [`Interpretation::all`].
+pub fn decode_guide(guide: &Guide) -> Result<GuideDecoding, AocError> {
+    let game = Game::rps();
+
+    // Number of rounds of each opponent's shape and response.
+    let mut rounds = [[0; 3]; 3];
+    for (i, (exp, resp)) in guide.iter().enumerate() {
+        let opp_move = Move::from_str(exp).map_err(|err| err.offset_lines(i))?;
+        let Some(column) = ["X", "Y", "Z"].iter().position(|xyz| *xyz == resp.trim()) else {
+            return Err(AocError::parse(Day2::DAY, resp, "Expected X, Y or Z.").offset_lines(i));
+        };
+        rounds[opp_move.shape()][column] += 1;
+    }
+
+    let mut scores = vec![];
+    for interpretation in Interpretation::all() {
+        let mut score = 0;
+        for (opponent, responses) in rounds.iter().enumerate() {
+            for (column, n_rounds) in responses.iter().enumerate() {
+                let shape = match interpretation {
+                    Interpretation::Moves(moves) => moves[column].shape(),
+                    Interpretation::Outcomes(outcomes) => {
+                        game.shape_for(opponent, outcomes[column]).ok_or_else(|| {
+                            AocError::unsolvable(
+                                Day2::DAY,
+                                format!("No shape against {opponent} to {:?}.", outcomes[column]),
+                            )
+                        })?
+                    }
+                };
+                score += n_rounds * rps_score(&game, shape, opponent)?;
             }
-            // Add score of move
-            your_score += your_move as usize;
         }
+        trace!("{interpretation} scores {score}");
+        scores.push(ScoredInterpretation {
+            interpretation,
+            score,
+        });
     }
-    Ok(your_score)
+
+    // `Interpretation::all` is never empty.
+    let best = *scores
+        .iter()
+        .rev()
+        .max_by_key(|scored| scored.score)
+        .unwrap();
+    let worst = *scores.iter().min_by_key(|scored| scored.score).unwrap();
+    Ok(GuideDecoding {
+        scores,
+        best,
+        worst,
+    })
 }
 
-pub fn rps_2(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let prompt = fs::read_to_string(fname)?;
+pub fn read_guide(reader: impl Read) -> Result<Guide, AocError> {
+    parse_guide(&read_input(reader)?)
+}
 
-    // Init score.
-    let mut your_score: usize = 0;
+pub fn rps(fname: &str) -> Result<usize, AocError> {
+    score_moves(&read_guide(File::open(fname)?)?)
+}
 
-    for res in prompt.trim().split("\n") {
-        let game_instr = res.split(" ").collect_vec();
-        // A - 1, B - 2, C - 3
-        // X - 6, Y - 3, Z - 0
-        if let (Some(exp), Some(resp)) = (game_instr.get(0), game_instr.get(1)) {
-            let opp_move = Move::from_str(exp)?;
-            let opp_outcome = Outcome::from_str(resp)?;
-            let your_outcome = match &opp_outcome {
-                Outcome::Win => Outcome::Loss,
-                Outcome::Draw => Outcome::Draw,
-                Outcome::Loss => Outcome::Win,
-            };
-            let des_move = opp_move
-                .get_outcome(&your_outcome)
-                .expect("No move to give desired outcome.");
-            let score_move = des_move as usize;
-            let score_outcome = opp_outcome as usize;
-            println!("{exp} - {resp}");
-            println!(
-                "{:?}, {:?}, {:?}, {:?}",
-                opp_move, des_move, score_move, score_outcome
-            );
+pub fn rps_2(fname: &str) -> Result<usize, AocError> {
+    score_outcomes(&read_guide(File::open(fname)?)?)
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    type Input = Guide;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_guide(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(score_moves(input)?.into())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(score_outcomes(input)?.into())
+    }
+}
+
+#[test]
+fn test_cyclic_games() {
+    let rps = Game::rps();
+    let rps_outcome = |mv: Move, opp: Move| rps.outcome(mv.shape(), opp.shape());
+    assert_eq!(rps_outcome(Move::Paper, Move::Rock), Some(Outcome::Win));
+    assert_eq!(rps_outcome(Move::Rock, Move::Scissor), Some(Outcome::Win));
+    assert_eq!(rps_outcome(Move::Rock, Move::Paper), Some(Outcome::Loss));
+    assert_eq!(Move::Rock.get_outcome(&Outcome::Win), Some(Move::Scissor));
 
-            // Add score of move and outcome
-            your_score += score_move + score_outcome;
+    // Rock, Spock, paper, lizard, scissors: Spock smashes scissors and lizard eats paper.
+    let rpsls = Game::rpsls();
+    assert_eq!(rpsls.outcome(1, 4), Some(Outcome::Win));
+    assert_eq!(rpsls.outcome(3, 2), Some(Outcome::Win));
+    assert_eq!(rpsls.outcome(3, 0), Some(Outcome::Loss));
+    assert_eq!(rpsls.score(3, 2), Some(4 + 6));
+    assert_eq!(Game::with_shapes(5), Some(rpsls.clone()));
+
+    for game in [rps, rpsls, Game::with_shapes(7).unwrap()] {
+        let n = game.n_shapes();
+        for shape in 0..n {
+            let wins = (0..n)
+                .filter(|opp| game.outcome(shape, *opp) == Some(Outcome::Win))
+                .count();
+            assert_eq!(wins, n / 2);
+            for outcome in [Outcome::Win, Outcome::Draw, Outcome::Loss] {
+                let yours = game.shape_for(shape, outcome).unwrap();
+                assert_eq!(game.outcome(yours, shape), Some(outcome));
+            }
         }
+        // Shapes outside the game have no outcome or score.
+        assert_eq!(game.outcome(n, 0), None);
+        assert_eq!(game.score(0, usize::MAX), None);
+        assert_eq!(game.shape_for(usize::MAX, Outcome::Loss), None);
     }
-    Ok(your_score)
+
+    assert_eq!(Game::with_shapes(0), None);
+    assert_eq!(Game::with_shapes(4), None);
+    let scores = OutcomeScores {
+        loss: 0,
+        draw: 1,
+        win: usize::MAX,
+    };
+    assert_eq!(Game::new(vec![1, 2, 3, 4], scores), None);
+    let solo = Game::new(vec![5], scores).unwrap();
+    assert_eq!(solo.shape_for(0, Outcome::Win), None);
+    assert_eq!(Game::new(vec![1, 2, 3], scores).unwrap().score(1, 0), None);
+}
+
+#[test]
+fn test_decode_guide() {
+    let guide = parse_guide("A Y\nB X\nC Z\n").unwrap();
+    let decoding = decode_guide(&guide).unwrap();
+    assert_eq!(decoding.scores.len(), 12);
+
+    let score_of = |interpretation| {
+        decoding
+            .scores
+            .iter()
+            .find(|scored| scored.interpretation == interpretation)
+            .unwrap()
+            .score
+    };
+    let moves = Interpretation::Moves([Move::Rock, Move::Paper, Move::Scissor]);
+    let outcomes = Interpretation::Outcomes([Outcome::Loss, Outcome::Draw, Outcome::Win]);
+    assert_eq!(score_of(moves), score_moves(&guide).unwrap());
+    assert_eq!(score_of(outcomes), score_outcomes(&guide).unwrap());
+
+    // Playing what beats each opponent's move wins every round.
+    let best = Interpretation::Moves([Move::Scissor, Move::Paper, Move::Rock]);
+    assert_eq!(decoding.best.interpretation, best);
+    assert_eq!(decoding.best.score, 3 + 6 + 1 + 6 + 2 + 6);
+    assert_eq!(decoding.worst.score, 3 + 1 + 2);
+    assert_eq!(best.to_string(), "X = Scissor, Y = Paper, Z = Rock");
 }
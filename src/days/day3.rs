@@ -1,7 +1,16 @@
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
-use std::error::Error;
-use std::fs;
+use std::fs::File;
+use std::io::Read;
+
+use tracing::{trace, warn};
+
+use crate::days::{
+    common::read_input,
+    error::AocError,
+    input,
+    solution::{Answer, Solution},
+};
 
 fn build_priorities() -> HashMap<char, usize> {
     let low_alpha = (b'a'..=b'z')
@@ -37,13 +46,39 @@ fn build_priorities() -> HashMap<char, usize> {
 
     alphabet
 }
-pub fn rucksack(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
+/// Parse rucksacks of items. Each item is a letter and both compartments hold the same number of items.
+pub fn parse_sacks(contents: &str) -> Result<Vec<String>, AocError> {
+    let mut sacks = vec![];
+    for (i, line) in input::non_blank_lines(contents) {
+        let sack = line.trim_start();
+        if let Some((col, item)) = sack.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+            return Err(AocError::parse_at(
+                Day3::DAY,
+                line,
+                &sack[col..col + item.len_utf8()],
+                "Expected an item from a-z or A-Z.",
+            )
+            .offset_lines(i));
+        }
+        if !sack.len().is_multiple_of(2) {
+            return Err(AocError::parse_at(
+                Day3::DAY,
+                line,
+                sack,
+                "Expected an even number of items to split into two compartments.",
+            )
+            .offset_lines(i));
+        }
+        sacks.push(sack.to_string())
+    }
+    Ok(sacks)
+}
+
+pub fn sack_priorities(sacks: &[String]) -> usize {
     let alphabet = build_priorities();
     let mut all_priorities: Vec<usize> = vec![];
 
-    for mut sack in contents.trim().split("\n") {
-        sack = sack.trim();
+    for sack in sacks.iter() {
         let n_items = sack.len();
         let first_comp = &sack[0..(n_items / 2)];
         let second_comp = &sack[(n_items / 2)..];
@@ -55,27 +90,26 @@ pub fn rucksack(fname: &str) -> Result<usize, Box<dyn Error>> {
             .intersection(&second_comp_item_cnts)
             .collect_vec();
 
-        if let Some(shared_item) = shared_items.get(0) {
-            let priority = alphabet.get(&shared_item).unwrap_or(&0);
-            // println!("{:?} - {}", shared_item, priority);
+        if let Some(shared_item) = shared_items.first() {
+            let priority = alphabet.get(shared_item).unwrap_or(&0);
+            trace!("{shared_item:?} - {priority}");
             all_priorities.push(*priority)
         } else {
-            println!("No shared items in sack: {sack}")
+            warn!("No shared items in sack: {sack}")
         }
     }
 
-    Ok(all_priorities.iter().sum())
+    all_priorities.iter().sum()
 }
 
-pub fn elf_groups(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
+pub fn group_priorities(sacks: &[String]) -> usize {
     let alphabet = build_priorities();
     let mut all_priorities: Vec<usize> = vec![];
 
-    for group in &contents.trim().split("\n").chunks(3) {
+    for group in &sacks.iter().chunks(3) {
         let shared_sack_contents = group
             .into_iter()
-            .map(|sack| sack.trim().chars().collect::<HashSet<char>>())
+            .map(|sack| sack.chars().collect::<HashSet<char>>())
             .reduce(|acc, cnt| acc.intersection(&cnt).cloned().collect());
         if let Some(shared_items) = shared_sack_contents {
             if let Some(first_elem) = shared_items.iter().next() {
@@ -83,5 +117,36 @@ pub fn elf_groups(fname: &str) -> Result<usize, Box<dyn Error>> {
             }
         }
     }
-    Ok(all_priorities.iter().sum())
+    all_priorities.iter().sum()
+}
+
+pub fn read_sacks(reader: impl Read) -> Result<Vec<String>, AocError> {
+    parse_sacks(&read_input(reader)?)
+}
+
+pub fn rucksack(fname: &str) -> Result<usize, AocError> {
+    Ok(sack_priorities(&read_sacks(File::open(fname)?)?))
+}
+
+pub fn elf_groups(fname: &str) -> Result<usize, AocError> {
+    Ok(group_priorities(&read_sacks(File::open(fname)?)?))
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_sacks(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(sack_priorities(input).into())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(group_priorities(input).into())
+    }
 }
@@ -1,81 +1,129 @@
-use std::{collections::HashSet, error::Error, fs, ops::RangeInclusive};
+use std::{fs::File, io::Read, ops::RangeInclusive};
 
 use itertools::Itertools;
 
+use crate::days::{
+    common::read_input,
+    error::AocError,
+    input,
+    solution::{Answer, Solution},
+};
+
 trait ToRange {
-    fn to_range(&self) -> Result<RangeInclusive<usize>, &'static str>;
+    fn to_range(&self) -> Option<RangeInclusive<usize>>;
 }
 
 impl ToRange for Vec<usize> {
     /// Convert vector of `usize` to inclusive range of values.
-    fn to_range(&self) -> Result<RangeInclusive<usize>, &'static str> {
-        let sorted_idxs = self.into_iter().sorted().collect_vec();
-        if let (Some(start), Some(stop)) = (sorted_idxs.get(0), sorted_idxs.get(1)) {
-            Ok(**start..=**stop)
+    fn to_range(&self) -> Option<RangeInclusive<usize>> {
+        let sorted_idxs = self.iter().sorted().collect_vec();
+        if let [start, stop] = sorted_idxs[..] {
+            Some(*start..=*stop)
         } else {
-            Err("Provided slice doesn't contain a start and stop.")
+            None
         }
     }
 }
 
-/// Convert string of format `#-#` to a full set of `#`'s.
-fn range_to_hashset(rng_str: &str) -> Result<HashSet<usize>, Box<dyn Error>> {
-    Ok(rng_str
-        .trim()
-        .split("-")
-        .map(|val| val.parse::<usize>().unwrap())
-        .collect_vec()
-        .to_range()?
-        .collect())
+/// Convert string of format `#-#` within a `line` to an inclusive range of sections.
+fn parse_range(line: &str, rng_str: &str) -> Result<RangeInclusive<usize>, AocError> {
+    let rng_str = rng_str.trim();
+    let bounds = rng_str
+        .split('-')
+        .map(|val| {
+            val.parse::<usize>()
+                .map_err(|_| AocError::parse_at(Day4::DAY, line, val, "Expected a section number."))
+        })
+        .collect::<Result<Vec<usize>, AocError>>()?;
+
+    bounds.to_range().ok_or_else(|| {
+        AocError::parse_at(
+            Day4::DAY,
+            line,
+            rng_str,
+            "Expected a range of sections like '2-4'.",
+        )
+    })
 }
 
-pub fn camp_cleanup_duplicates(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
-    let pairs = contents
-        .trim()
-        .split("\n")
-        .map(|assignments| assignments.split(",").collect_vec())
-        .collect_vec();
-    // Number of pairs where one elf's assignments are a subset of another.
-    let mut n_full_cont_pairs = 0;
+/// Pair of elves' section assignments.
+pub type Assignments = (RangeInclusive<usize>, RangeInclusive<usize>);
 
-    for pair in pairs.iter() {
-        if let (Some(a_1), Some(a_2)) = (pair.get(0), pair.get(1)) {
-            // Convert ranges to hashset.
-            let a_rng_1: HashSet<usize> = range_to_hashset(&a_1)?;
-            let a_rng_2: HashSet<usize> = range_to_hashset(&a_2)?;
+pub fn parse_assignments(contents: &str) -> Result<Vec<Assignments>, AocError> {
+    let mut all_assignments = vec![];
 
-            // Check if one belongs in the other.
-            if a_rng_1.is_subset(&a_rng_2) || a_rng_2.is_subset(&a_rng_1) {
-                n_full_cont_pairs += 1
-            }
-        }
+    for (i, line) in input::non_blank_lines(contents) {
+        let Some((a_1, a_2)) = line.split(',').collect_tuple() else {
+            return Err(AocError::parse(
+                Day4::DAY,
+                line,
+                "Expected a pair of assignments separated by ','.",
+            )
+            .offset_lines(i));
+        };
+        let a_rng_1 = parse_range(line, a_1).map_err(|err| err.offset_lines(i))?;
+        let a_rng_2 = parse_range(line, a_2).map_err(|err| err.offset_lines(i))?;
+        all_assignments.push((a_rng_1, a_rng_2))
     }
-    Ok(n_full_cont_pairs)
+    Ok(all_assignments)
 }
 
-pub fn camp_cleanup_overlap(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
-    let pairs = contents
-        .trim()
-        .split("\n")
-        .map(|assignments| assignments.split(",").collect_vec())
-        .collect_vec();
+pub fn count_duplicates(all_assignments: &[Assignments]) -> usize {
     // Number of pairs where one elf's assignments are a subset of another.
     let mut n_full_cont_pairs = 0;
 
-    for pair in pairs.iter() {
-        if let (Some(a_1), Some(a_2)) = (pair.get(0), pair.get(1)) {
-            // Convert ranges to hashset.
-            let a_rng_1: HashSet<usize> = range_to_hashset(&a_1)?;
-            let a_rng_2: HashSet<usize> = range_to_hashset(&a_2)?;
-
-            // Check if one belongs in the other.
-            let overlap_1_2 = a_rng_1.intersection(&a_rng_2).collect_vec();
-            if !overlap_1_2.is_empty() {
-                n_full_cont_pairs += 1
-            }
+    for (a_rng_1, a_rng_2) in all_assignments.iter() {
+        // Check if one belongs in the other.
+        let contains = |outer: &RangeInclusive<usize>, inner: &RangeInclusive<usize>| {
+            outer.contains(inner.start()) && outer.contains(inner.end())
+        };
+        if contains(a_rng_1, a_rng_2) || contains(a_rng_2, a_rng_1) {
+            n_full_cont_pairs += 1
         }
     }
-    Ok(n_full_cont_pairs)
+    n_full_cont_pairs
+}
+
+pub fn count_overlaps(all_assignments: &[Assignments]) -> usize {
+    // Number of pairs where one elf's assignments overlap another.
+    let mut n_overlap_pairs = 0;
+
+    for (a_rng_1, a_rng_2) in all_assignments.iter() {
+        // Check if one overlaps the other.
+        if a_rng_1.start() <= a_rng_2.end() && a_rng_2.start() <= a_rng_1.end() {
+            n_overlap_pairs += 1
+        }
+    }
+    n_overlap_pairs
+}
+
+pub fn read_assignments(reader: impl Read) -> Result<Vec<Assignments>, AocError> {
+    parse_assignments(&read_input(reader)?)
+}
+
+pub fn camp_cleanup_duplicates(fname: &str) -> Result<usize, AocError> {
+    Ok(count_duplicates(&read_assignments(File::open(fname)?)?))
+}
+
+pub fn camp_cleanup_overlap(fname: &str) -> Result<usize, AocError> {
+    Ok(count_overlaps(&read_assignments(File::open(fname)?)?))
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    type Input = Vec<Assignments>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_assignments(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(count_duplicates(input).into())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(count_overlaps(input).into())
+    }
 }
@@ -2,30 +2,40 @@ use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::VecDeque;
-use std::error::Error;
-use std::fs;
+use std::fs::File;
+use std::io::Read;
+
+use tracing::trace;
+
+use crate::days::{
+    common::read_input,
+    error::AocError,
+    input::{self, Block},
+    solution::{Answer, Solution},
+};
 
 lazy_static! {
     static ref RGX_INSTRUCTIONS: Regex = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
 }
 
-fn parse_stack_text(stack_str: &str) -> Result<Vec<VecDeque<char>>, Box<dyn Error>> {
-    let mut lines = stack_str.lines().collect_vec();
+/// Parse the lines of the drawing of the starting stacks. The last line labels each stack.
+fn parse_stack_text(drawing: &[&str]) -> Result<Vec<VecDeque<char>>, AocError> {
     let mut stacks: Vec<VecDeque<char>> = vec![];
 
-    let stack_str_idx = lines
-        .last()
-        .map(|stack_nums| {
-            stack_nums
-                .trim_end()
-                .chars()
-                .enumerate()
-                .filter_map(|(i, char)| if char != ' ' { Some(i) } else { None })
-                .collect_vec()
-        })
-        .expect("No stack numbers found.");
-
-    lines.pop();
+    let Some((stack_nums, lines)) = drawing.split_last() else {
+        return Err(AocError::parse(Day5::DAY, "", "No stack numbers found."));
+    };
+    let stack_str_idx = stack_nums
+        .chars()
+        .enumerate()
+        .filter_map(|(i, char)| if char != ' ' { Some(i) } else { None })
+        .collect_vec();
+    if stack_str_idx.is_empty() {
+        return Err(
+            AocError::parse(Day5::DAY, stack_nums, "No stack numbers found.")
+                .offset_lines(lines.len()),
+        );
+    }
 
     // Get max number of stacks and generate a double-ended queue for each stack.
     for _ in 0..stack_str_idx.len() {
@@ -34,14 +44,23 @@ fn parse_stack_text(stack_str: &str) -> Result<Vec<VecDeque<char>>, Box<dyn Erro
     }
 
     // Parse from top to bottom so must reverse to ensure order is correct.
-    for line in lines.into_iter().rev() {
+    for (i, line) in lines.iter().enumerate().rev() {
         for (stack_idx, line_idx) in stack_str_idx.iter().enumerate() {
-            if let Some(item) = line.chars().nth(*line_idx).filter(|char| *char != ' ') {
-                if let Some(stack) = stacks.get_mut(stack_idx) {
-                    stack.push_back(item)
-                } else {
-                    println!("Stack {stack_idx} not found.")
+            if let Some((col, item)) = line
+                .char_indices()
+                .nth(*line_idx)
+                .filter(|(_, char)| *char != ' ')
+            {
+                if !item.is_ascii_alphabetic() {
+                    return Err(AocError::parse_at(
+                        Day5::DAY,
+                        line,
+                        &line[col..col + item.len_utf8()],
+                        "Expected a crate labeled with a letter.",
+                    )
+                    .offset_lines(i));
                 }
+                stacks[stack_idx].push_back(item)
             }
         }
     }
@@ -49,116 +68,213 @@ fn parse_stack_text(stack_str: &str) -> Result<Vec<VecDeque<char>>, Box<dyn Erro
     Ok(stacks)
 }
 
-#[derive(Debug)]
-struct StackParseError;
-
-impl std::fmt::Display for StackParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Failed to parse stack from given prompt.")
-    }
+/// Instruction to move some number of crates between two stacks.
+#[derive(Debug, Clone)]
+pub struct CrateMove {
+    pub n_crates: usize,
+    pub from_stack_idx: usize,
+    pub to_stack_idx: usize,
 }
-impl Error for StackParseError {}
 
-pub fn crate_mover_9000(fname: &str) -> Result<Vec<char>, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
-    let stack_instructions = contents.split("\n\r\n").collect_vec();
+/// Starting stacks of crates and the rearrangement procedure.
+#[derive(Debug, Clone)]
+pub struct Supplies {
+    pub stacks: Vec<VecDeque<char>>,
+    pub moves: Vec<CrateMove>,
+}
 
+pub fn parse_supplies(contents: &str) -> Result<Supplies, AocError> {
     // Unpack stack and the moving instructions.
-    if let (Some(stack), Some(instructions)) =
-        (stack_instructions.get(0), stack_instructions.get(1))
-    {
-        // Get stacks from text.
-        let mut stacks = parse_stack_text(&stack)?;
-
-        // Iterate through instructions.
-        for line in instructions.lines() {
-            // Match on pattern in instructions.
-            if let Some(cap) = RGX_INSTRUCTIONS.captures(line) {
-                if let (Some(n_crates_mtch), Some(from_stack_mtch), Some(to_stack_mtch)) =
-                    (cap.get(1), cap.get(2), cap.get(3))
-                {
-                    let n_crates: usize = n_crates_mtch.as_str().trim().parse()?;
-                    let from_stack_idx: usize = from_stack_mtch.as_str().parse()?;
-                    let to_stack_idx: usize = to_stack_mtch.as_str().parse()?;
-
-                    // Move n crates from a stack
-                    for _ in 0..n_crates {
-                        let mut item: Option<char> = None;
-                        if let Some(stack_from) = stacks.get_mut(from_stack_idx - 1) {
-                            item = stack_from.pop_back();
-                        };
-                        if let (Some(stack_to), Some(moved_item)) =
-                            (stacks.get_mut(to_stack_idx - 1), item)
-                        {
-                            stack_to.push_back(moved_item)
-                        }
-                    }
-                    // println!("Moved {} from {} to {}", n_crates, from_stack_idx, to_stack_idx);
-                }
+    let blocks = input::blocks(contents);
+    let Some((drawing, instructions)) = blocks.split_first().filter(|(_, rest)| !rest.is_empty())
+    else {
+        return Err(AocError::parse(
+            Day5::DAY,
+            input::lines(contents).next().unwrap_or_default().1,
+            "Expected a drawing of the stacks and the moves separated by a blank line.",
+        ));
+    };
+
+    // Get stacks from text.
+    let stacks = parse_stack_text(&drawing.lines).map_err(|err| err.offset_lines(drawing.start))?;
+    let mut moves = vec![];
+
+    // Iterate through instructions.
+    for (i, line) in instructions.iter().flat_map(Block::numbered_lines) {
+        let cap = RGX_INSTRUCTIONS.captures(line).ok_or_else(|| {
+            AocError::parse(
+                Day5::DAY,
+                line,
+                "Expected a move like 'move 1 from 2 to 1'.",
+            )
+            .offset_lines(i)
+        })?;
+        // Every group is part of the match, so they are always captured.
+        let number = |group: usize| {
+            cap[group].parse::<usize>().map_err(|_| {
+                AocError::parse_at(Day5::DAY, line, &cap[group], "Expected a number.")
+                    .offset_lines(i)
+            })
+        };
+        let (n_crates, from_stack_idx, to_stack_idx) = (number(1)?, number(2)?, number(3)?);
+
+        // Stacks are numbered from 1.
+        for (stack_idx, group) in [(from_stack_idx, 2), (to_stack_idx, 3)] {
+            if !(1..=stacks.len()).contains(&stack_idx) {
+                return Err(AocError::parse_at(
+                    Day5::DAY,
+                    line,
+                    &cap[group],
+                    format!("Expected a stack from 1 to {}.", stacks.len()),
+                )
+                .offset_lines(i));
             }
         }
+        moves.push(CrateMove {
+            n_crates,
+            from_stack_idx,
+            to_stack_idx,
+        })
+    }
+    Ok(Supplies { stacks, moves })
+}
+
+fn top_crates(stacks: &mut [VecDeque<char>]) -> Vec<char> {
+    stacks
+        .iter_mut()
+        .map(|stack| stack.pop_back().unwrap_or(' '))
+        .collect_vec()
+}
+
+/// Take the crates of a move off the top of its source stack, bottom crate first.
+/// Returns them with the position of the destination stack.
+fn lift_crates(
+    stacks: &mut [VecDeque<char>],
+    crate_move: &CrateMove,
+) -> Result<(VecDeque<char>, usize), AocError> {
+    // Stacks are numbered from 1.
+    let n_stacks = stacks.len();
+    let stack_idx = |stack_num: usize| {
+        stack_num
+            .checked_sub(1)
+            .filter(|idx| *idx < n_stacks)
+            .ok_or_else(|| {
+                AocError::unsolvable(
+                    Day5::DAY,
+                    format!("No stack {stack_num} among {n_stacks} stacks."),
+                )
+            })
+    };
+    let (from_idx, to_idx) = (
+        stack_idx(crate_move.from_stack_idx)?,
+        stack_idx(crate_move.to_stack_idx)?,
+    );
 
-        // println!("{:#?}", stacks);
-        let last_items = stacks
-            .iter_mut()
-            .map(|stack| stack.pop_back().unwrap_or(' '))
-            .collect_vec();
-        Ok(last_items)
-    } else {
-        Err(Box::new(StackParseError))
+    let stack_from = &mut stacks[from_idx];
+    let start = stack_from
+        .len()
+        .checked_sub(crate_move.n_crates)
+        .ok_or_else(|| {
+            AocError::unsolvable(
+                Day5::DAY,
+                format!(
+                    "Can't move {} crates from stack {}, which has {}.",
+                    crate_move.n_crates,
+                    crate_move.from_stack_idx,
+                    stack_from.len()
+                ),
+            )
+        })?;
+    trace!(
+        "Moved {} from {} to {}",
+        crate_move.n_crates,
+        crate_move.from_stack_idx,
+        crate_move.to_stack_idx
+    );
+    Ok((stack_from.split_off(start), to_idx))
+}
+
+/// Stacks after the CrateMover 9000 moves crates one at a time.
+pub fn rearrange_9000(supplies: &Supplies) -> Result<Vec<VecDeque<char>>, AocError> {
+    let mut stacks = supplies.stacks.clone();
+
+    for crate_move in supplies.moves.iter() {
+        // Moving one at a time puts the top crate at the bottom.
+        let (crates, to_idx) = lift_crates(&mut stacks, crate_move)?;
+        stacks[to_idx].extend(crates.into_iter().rev());
     }
+    Ok(stacks)
 }
 
-pub fn crate_mover_9001(fname: &str) -> Result<Vec<char>, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
-    let stack_instructions = contents.split("\n\r\n").collect_vec();
+/// Stacks after the CrateMover 9001 moves several crates at once, keeping their order.
+pub fn rearrange_9001(supplies: &Supplies) -> Result<Vec<VecDeque<char>>, AocError> {
+    let mut stacks = supplies.stacks.clone();
 
-    // Unpack stack and the moving instructions.
-    if let (Some(stack), Some(instructions)) =
-        (stack_instructions.get(0), stack_instructions.get(1))
-    {
-        // Get stacks from text.
-        let mut stacks = parse_stack_text(&stack)?;
-
-        // Iterate through instructions.
-        for line in instructions.lines() {
-            // Match on pattern in instructions.
-            if let Some(cap) = RGX_INSTRUCTIONS.captures(line) {
-                if let (Some(n_crates_mtch), Some(from_stack_mtch), Some(to_stack_mtch)) =
-                    (cap.get(1), cap.get(2), cap.get(3))
-                {
-                    let n_crates: usize = n_crates_mtch.as_str().trim().parse()?;
-                    let from_stack_idx: usize = from_stack_mtch.as_str().parse()?;
-                    let to_stack_idx: usize = to_stack_mtch.as_str().parse()?;
-
-                    // Move n crates from a stack
-                    // Use intermediate vector to hold moved items.
-                    let mut crate_holder: Vec<char> = vec![];
-                    for _ in 0..n_crates {
-                        if let Some(stack_from) = stacks.get_mut(from_stack_idx - 1) {
-                            if let Some(item) = stack_from.pop_back() {
-                                crate_holder.push(item);
-                            }
-                        };
-                    }
-                    if let Some(stack_to) = stacks.get_mut(to_stack_idx - 1) {
-                        // From intermediate crate holder, reverse order to ensure moving multiple crates retain order.
-                        for held_item in crate_holder.into_iter().rev() {
-                            stack_to.push_back(held_item)
-                        }
-                    }
-                    // println!("Moved {} from {} to {}", n_crates, from_stack_idx, to_stack_idx);
-                }
-            }
-        }
+    for crate_move in supplies.moves.iter() {
+        let (crates, to_idx) = lift_crates(&mut stacks, crate_move)?;
+        stacks[to_idx].extend(crates);
+    }
+    Ok(stacks)
+}
+
+pub fn move_crates_9000(supplies: &Supplies) -> Result<Vec<char>, AocError> {
+    Ok(top_crates(&mut rearrange_9000(supplies)?))
+}
+
+pub fn move_crates_9001(supplies: &Supplies) -> Result<Vec<char>, AocError> {
+    Ok(top_crates(&mut rearrange_9001(supplies)?))
+}
+
+pub fn read_supplies(reader: impl Read) -> Result<Supplies, AocError> {
+    parse_supplies(&read_input(reader)?)
+}
+
+pub fn crate_mover_9000(fname: &str) -> Result<Vec<char>, AocError> {
+    move_crates_9000(&read_supplies(File::open(fname)?)?)
+}
+
+pub fn crate_mover_9001(fname: &str) -> Result<Vec<char>, AocError> {
+    move_crates_9001(&read_supplies(File::open(fname)?)?)
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    type Input = Supplies;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_supplies(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(Answer::Text(move_crates_9000(input)?.iter().collect()))
+    }
 
-        // println!("{:#?}", stacks);
-        let last_items = stacks
-            .iter_mut()
-            .map(|stack| stack.pop_back().unwrap_or(' '))
-            .collect_vec();
-        Ok(last_items)
-    } else {
-        Err(Box::new(StackParseError))
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(Answer::Text(move_crates_9001(input)?.iter().collect()))
     }
 }
+
+#[test]
+fn test_oversized_moves() {
+    let supplies = parse_supplies(&format!(
+        "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove {} from 1 to 2\n",
+        usize::MAX
+    ))
+    .unwrap();
+    assert!(matches!(
+        rearrange_9000(&supplies),
+        Err(AocError::Unsolvable { day: 5, .. })
+    ));
+
+    let supplies = Supplies {
+        moves: vec![CrateMove {
+            n_crates: 1,
+            from_stack_idx: 0,
+            to_stack_idx: 1,
+        }],
+        ..supplies
+    };
+    assert!(rearrange_9001(&supplies).is_err());
+}
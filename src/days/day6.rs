@@ -1,63 +1,114 @@
-use std::{error::Error, fs};
+use std::{fs::File, io::Read};
 
 use itertools::Itertools;
+use tracing::debug;
+
+use crate::days::{
+    common::read_input,
+    error::AocError,
+    solution::{Answer, Solution},
+};
 
 const PACKET_LEN: usize = 4;
 const MSG_LEN: usize = 14;
-type Packet = (char, char, char, char);
 
-#[derive(Debug)]
-struct BufferReadError {}
+/// Number of characters read once the last `len` characters are all different.
+fn find_marker(buffer: &str, len: usize) -> Option<usize> {
+    // Parsed buffers are ASCII, so each byte is a character.
+    let marker_end = buffer
+        .as_bytes()
+        .windows(len)
+        .position(|window| window.iter().all_unique())
+        .map(|i| i + len)?;
+    debug!(
+        "After {marker_end} chars - {}",
+        String::from_utf8_lossy(&buffer.as_bytes()[marker_end - len..marker_end])
+    );
+    Some(marker_end)
+}
 
-impl std::fmt::Display for BufferReadError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Can't read buffer.")
-    }
+pub fn packet_marker(buffer: &str) -> Result<usize, AocError> {
+    find_marker(buffer, PACKET_LEN)
+        .ok_or_else(|| AocError::unsolvable(Day6::DAY, "No start-of-packet marker in buffer."))
 }
 
-impl Error for BufferReadError {}
-
-pub fn read_comm_packet(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
-    let mut first_marker: Option<usize> = None;
-
-    for (i, (char_1, char_2, char_3, char_4)) in
-        contents.chars().tuple_windows::<Packet>().enumerate()
-    {
-        let uniq_chars = [&char_1, &char_2, &char_3, &char_4]
-            .into_iter()
-            .unique()
-            .collect_vec();
-        if uniq_chars.len() == PACKET_LEN {
-            // println!("After {} chars - {char_1}{char_2}{char_3}{char_4}", i + PACKET_LEN);
-            first_marker = Some(i + PACKET_LEN);
-            break;
-        }
-    }
-    if let Some(marker_idx) = first_marker {
-        Ok(marker_idx)
-    } else {
-        Err(Box::new(BufferReadError {}))
+pub fn message_marker(buffer: &str) -> Result<usize, AocError> {
+    find_marker(buffer, MSG_LEN)
+        .ok_or_else(|| AocError::unsolvable(Day6::DAY, "No start-of-message marker in buffer."))
+}
+
+/// Parse the datastream buffer, which must be ASCII without whitespace after trimming it.
+pub fn parse_buffer(contents: &str) -> Result<String, AocError> {
+    let buffer = contents.trim();
+    let invalid = buffer
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii() || c.is_whitespace());
+    if let Some((i, c)) = invalid {
+        let reason = if c.is_ascii() {
+            "Expected no whitespace within the buffer."
+        } else {
+            "Expected an ASCII character."
+        };
+        // Escape whitespace like a newline so that the error stays on one line.
+        return Err(AocError::Parse {
+            day: Some(Day6::DAY),
+            line: 1,
+            column: buffer[..i].chars().count() + 1,
+            text: c.escape_debug().to_string(),
+            reason: reason.to_string(),
+        });
     }
+    Ok(buffer.to_string())
 }
 
-pub fn read_comm_message(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
-    let mut first_marker: Option<usize> = None;
+pub fn read_buffer(reader: impl Read) -> Result<String, AocError> {
+    parse_buffer(&read_input(reader)?)
+}
 
-    for i in 0..contents.len() {
-        if let Some(message_marker) = contents.get(i..i + MSG_LEN) {
-            let message_chars = message_marker.chars().unique().collect_vec();
-            if message_chars.len() == MSG_LEN {
-                println!("After {} chars - {message_marker}", i + MSG_LEN);
-                first_marker = Some(i + MSG_LEN);
-                break;
-            }
-        }
+pub fn read_comm_packet(fname: &str) -> Result<usize, AocError> {
+    packet_marker(&read_buffer(File::open(fname)?)?)
+}
+
+pub fn read_comm_message(fname: &str) -> Result<usize, AocError> {
+    message_marker(&read_buffer(File::open(fname)?)?)
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_buffer(input)
     }
-    if let Some(marker_idx) = first_marker {
-        Ok(marker_idx)
-    } else {
-        Err(Box::new(BufferReadError {}))
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(packet_marker(input)?.into())
     }
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(message_marker(input)?.into())
+    }
+}
+
+#[test]
+fn test_invalid_buffer() {
+    let buffer = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+    assert_eq!(packet_marker(buffer).unwrap(), 7);
+    assert_eq!(message_marker(buffer).unwrap(), 19);
+    assert_eq!(parse_buffer(&format!("{buffer}\n")).unwrap(), buffer);
+
+    assert_eq!(
+        parse_buffer("mjqjpé").unwrap_err().to_string(),
+        "Day 6, line 1, column 6: Expected an ASCII character. Found 'é'."
+    );
+    assert_eq!(
+        parse_buffer("abc\ndefghijklmn\n").unwrap_err().to_string(),
+        "Day 6, line 1, column 4: Expected no whitespace within the buffer. Found '\\n'."
+    );
+    assert_eq!(
+        parse_buffer(" mjq jp ").unwrap_err().to_string(),
+        "Day 6, line 1, column 4: Expected no whitespace within the buffer. Found ' '."
+    );
 }
@@ -1,12 +1,22 @@
 use std::{
     cell::RefCell,
     collections::HashMap,
-    error::Error,
     fs,
+    io::Read,
+    mem,
     rc::{Rc, Weak},
+    str::FromStr,
 };
 
 use itertools::Itertools;
+use tracing::trace;
+
+use crate::days::{
+    common::read_input,
+    error::AocError,
+    input,
+    solution::{Answer, Solution},
+};
 
 #[derive(Debug, Clone)]
 pub struct File {
@@ -27,112 +37,181 @@ pub struct FileSystem {
     dirs: Vec<Rc<Dir>>,
 }
 
-#[derive(Debug)]
-struct FileSystemError {}
+impl File {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 
-impl std::fmt::Display for FileSystemError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Failure for Files.")
+    pub fn size(&self) -> usize {
+        self.size
     }
 }
-impl Error for FileSystemError {}
 
 impl Dir {
-    fn du(&self) -> usize {
-        let mut dir_size: usize = 0;
-        for file in &*self.files.borrow() {
-            dir_size += file.size
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn files(&self) -> Vec<File> {
+        self.files.borrow().clone()
+    }
+
+    pub fn children(&self) -> Vec<Rc<Dir>> {
+        self.children.borrow().clone()
+    }
+
+    /// Total size of the files in this directory, without its subdirectories.
+    fn files_size(&self) -> Result<usize, AocError> {
+        self.files.borrow().iter().try_fold(0, |dir_size, file| {
+            checked_size(dir_size, file.size, &self.name)
+        })
+    }
+
+    /// Total size of the files in this directory and all of its subdirectories.
+    pub fn du(&self) -> Result<usize, AocError> {
+        let mut dir_size = self.files_size()?;
+        // Walk the subdirectories with a stack, since they may nest deeper than the call stack.
+        let mut subdirs = self.children();
+        while let Some(dir) = subdirs.pop() {
+            dir_size = checked_size(dir_size, dir.files_size()?, &self.name)?;
+            subdirs.extend(dir.children.borrow().iter().cloned());
         }
-        for dir in &*self.children.borrow() {
-            dir_size += dir.du()
+        Ok(dir_size)
+    }
+}
+
+impl Drop for Dir {
+    /// Drop subdirectories that nothing else owns in a loop instead of recursively, so that deep
+    /// directories don't overflow the stack.
+    fn drop(&mut self) {
+        let mut subdirs = mem::take(self.children.get_mut());
+        while let Some(dir) = subdirs.pop() {
+            if let Ok(mut dir) = Rc::try_unwrap(dir) {
+                subdirs.append(dir.children.get_mut());
+            }
         }
-        dir_size
     }
 }
 
+/// Sum of two sizes within a directory, unless it's too large for a `usize`.
+fn checked_size(size: usize, other: usize, dir_name: &str) -> Result<usize, AocError> {
+    size.checked_add(other).ok_or_else(|| {
+        AocError::unsolvable(
+            Day7::DAY,
+            format!("Size of directory '{dir_name}' is too large."),
+        )
+    })
+}
+
 impl FileSystem {
-    fn new(fname: &str) -> Result<FileSystem, Box<dyn Error>> {
-        let contents = fs::read_to_string(fname)?;
-        // Store directories and files.
-        let mut dirs: Vec<Rc<Dir>> = vec![Rc::new(Dir {
+    pub fn new(fname: &str) -> Result<FileSystem, AocError> {
+        FileSystem::from_reader(fs::File::open(fname)?)
+    }
+
+    pub fn from_reader(reader: impl Read) -> Result<FileSystem, AocError> {
+        FileSystem::from_str(&read_input(reader)?)
+    }
+
+    /// All directories, starting with the root. Each directory comes after its parent.
+    pub fn dirs(&self) -> &[Rc<Dir>] {
+        &self.dirs
+    }
+
+    /// Total size of each directory, in the order of `dirs`.
+    ///
+    /// Sizes are added up in one pass from the last directory to the first, so each directory's
+    /// size is complete before it's added to its parent's.
+    pub fn dir_sizes(&self) -> Result<Vec<usize>, AocError> {
+        let positions: HashMap<*const Dir, usize> = self
+            .dirs
+            .iter()
+            .enumerate()
+            .map(|(i, dir)| (Rc::as_ptr(dir), i))
+            .collect();
+        let mut sizes = self
+            .dirs
+            .iter()
+            .map(|dir| dir.files_size())
+            .collect::<Result<Vec<usize>, AocError>>()?;
+
+        for (i, dir) in self.dirs.iter().enumerate().rev() {
+            let parent = dir.parent.borrow().upgrade();
+            if let Some(&parent_idx) = parent.and_then(|parent| positions.get(&Rc::as_ptr(&parent)))
+            {
+                sizes[parent_idx] =
+                    checked_size(sizes[parent_idx], sizes[i], &self.dirs[parent_idx].name)?;
+            }
+        }
+        Ok(sizes)
+    }
+}
+
+impl FromStr for FileSystem {
+    type Err = AocError;
+
+    /// Build a `FileSystem` from the terminal output of `cd` and `ls` commands.
+    fn from_str(contents: &str) -> Result<Self, Self::Err> {
+        let root = Rc::new(Dir {
             name: "/".to_string(),
             children: RefCell::new(vec![]),
             files: RefCell::new(vec![]),
             parent: RefCell::new(Weak::new()),
-        })];
-        let curr_dir: RefCell<Option<Rc<Dir>>> = RefCell::new(Some(dirs.get(0).unwrap().clone()));
+        });
+        // Store directories and files.
+        let mut dirs: Vec<Rc<Dir>> = vec![root.clone()];
+        let mut curr_dir = root.clone();
 
         // Iterate through the commmands and their outputs.
-        for mut cmd_out in contents.trim().split("$") {
-            cmd_out = cmd_out.trim();
-            // If command is to change directory.
-            if cmd_out.starts_with("cd") {
-                if let Some(name) = cmd_out.split(" ").nth(1) {
-                    if name == ".." {
-                        let parent_dir = if let Some(c_dir) = &*curr_dir.borrow() {
-                            c_dir.parent.borrow().upgrade()
-                        } else {
-                            None
-                        };
-                        *curr_dir.borrow_mut() = parent_dir;
-                    } else if name == "/" {
-                        // Skip root directory.
-                        continue;
-                    } else {
-                        if let Ok(mut current_dir) = curr_dir.try_borrow_mut() {
-                            if let Some(cdir) = &*current_dir {
-                                let next_dir = cdir
-                                    .children
-                                    .borrow()
-                                    .iter()
-                                    .find(|dir| dir.name == name)
-                                    .cloned();
-                                *current_dir = next_dir
-                            }
-                        } else {
-                            println!("Can't get current.")
-                        }
-                    }
-                } else {
-                    continue;
-                };
-            } else {
-                // Otherwise is viewing directory structure.
-                for mut desc_file in cmd_out.lines() {
-                    desc_file = desc_file.trim();
-
-                    if desc_file == "ls" {
-                        continue;
-                    }
-                    let split_desc_file = desc_file.split(" ").collect_vec();
-                    if let (Some(desc), Some(name)) =
-                        (split_desc_file.get(0), split_desc_file.get(1))
-                    {
-                        // Add children to curr_dir ref
-                        if *desc == "dir" {
-                            if let Some(wd) = &*curr_dir.borrow_mut() {
-                                // Get child directory in current
-                                // Init with all fields empty.
-                                let directory = Rc::new(Dir {
-                                    name: name.to_string(),
-                                    children: RefCell::new(vec![]),
-                                    files: RefCell::new(vec![]),
-                                    parent: RefCell::new(Rc::downgrade(&wd)),
-                                });
-                                dirs.push(directory.clone());
-                                wd.children.borrow_mut().push(directory);
-                            }
-                        } else {
-                            let file = File {
-                                name: name.to_string(),
-                                size: desc.parse::<usize>()?,
-                            };
-                            // Get a mut ref to curr directory.
-                            if let Some(wd) = &*curr_dir.borrow_mut() {
-                                wd.files.borrow_mut().push(file);
-                            }
-                        }
-                    }
+        for (i, line) in input::lines(contents) {
+            let line_err = |token: &str, reason: &str| {
+                AocError::parse_at(Day7::DAY, line, token, reason).offset_lines(i)
+            };
+
+            match line.split_whitespace().collect_vec()[..] {
+                [] | ["$", "ls"] => continue,
+                ["$", "cd", "/"] => curr_dir = root.clone(),
+                ["$", "cd", name @ ".."] => {
+                    let parent_dir = curr_dir.parent.borrow().upgrade();
+                    curr_dir = parent_dir
+                        .ok_or_else(|| line_err(name, "Root directory has no parent."))?;
+                }
+                ["$", "cd", name] => {
+                    let next_dir = curr_dir
+                        .children
+                        .borrow()
+                        .iter()
+                        .find(|dir| dir.name == name)
+                        .cloned();
+                    curr_dir = next_dir.ok_or_else(|| {
+                        line_err(name, "No such directory in the current directory.")
+                    })?;
+                }
+                ["$", ..] => return Err(line_err(line.trim(), "Expected 'cd <dir>' or 'ls'.")),
+                // Otherwise is viewing directory structure. Add children to current directory.
+                ["dir", name] => {
+                    let directory = Rc::new(Dir {
+                        name: name.to_string(),
+                        children: RefCell::new(vec![]),
+                        files: RefCell::new(vec![]),
+                        parent: RefCell::new(Rc::downgrade(&curr_dir)),
+                    });
+                    dirs.push(directory.clone());
+                    curr_dir.children.borrow_mut().push(directory);
+                }
+                [size, name] => {
+                    let file = File {
+                        name: name.to_string(),
+                        size: size
+                            .parse::<usize>()
+                            .map_err(|_| line_err(size, "Expected a file size."))?,
+                    };
+                    curr_dir.files.borrow_mut().push(file);
+                }
+                _ => {
+                    return Err(line_err(
+                        line.trim(),
+                        "Expected a command, 'dir <name>' or '<size> <name>'.",
+                    ))
                 }
             }
         }
@@ -140,66 +219,118 @@ impl FileSystem {
     }
 }
 
-pub fn sum_file_system(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let file_system = FileSystem::new(fname)?;
-
-    // Iterate through all dirs and calculate directory size.
-    // du() calculates disk size for current directory and nested directories.
-    let dir_sizes: HashMap<String, usize> = file_system
+pub fn sum_small_dirs(file_system: &FileSystem) -> Result<usize, AocError> {
+    let mut total_disk_size: usize = 0;
+    for (i, (dir, size)) in file_system
         .dirs
         .iter()
+        .zip(file_system.dir_sizes()?)
         .enumerate()
-        .filter_map(|(i, dir)| {
-            let dir_size = dir.du();
-            let new_dirname = format!("{i}_{}", dir.name);
-            if dir_size < 100000 {
-                Some((new_dirname, dir_size))
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    let mut total_disk_size: usize = 0;
-    for (dir, size) in dir_sizes.iter() {
-        println!("{:?} - {}", dir, size);
-        total_disk_size += size;
+    {
+        if size <= 100000 {
+            trace!("{i}_{} - {size}", dir.name);
+            total_disk_size += size;
+        }
     }
 
     Ok(total_disk_size)
 }
 
-pub fn free_space_file_system(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let file_system = FileSystem::new(fname)?;
+pub fn smallest_dir_to_free(file_system: &FileSystem) -> Result<usize, AocError> {
     const DISK_SIZE: usize = 70_000_000;
     const REQ_DISK_SPACE: usize = 30_000_000;
 
-    // Iterate through all dirs and calculate directory size.
-    // du() calculates disk size for current directory and nested directories.
-    let dir_sizes: Vec<(String, usize)> = file_system
-        .dirs
-        .iter()
-        .enumerate()
-        .filter_map(|(i, dir)| {
-            let dir_size = dir.du();
-            let new_dirname = format!("{i}_{}", dir.name);
-            Some((new_dirname, dir_size))
+    let dir_sizes = file_system.dir_sizes()?;
+
+    // First dir is root dir.
+    let used_space = dir_sizes.first().copied().unwrap_or(0);
+    // Space to free, which is more than the required space if the files don't fit on the disk.
+    let needed_space = used_space
+        .saturating_add(REQ_DISK_SPACE)
+        .saturating_sub(DISK_SIZE);
+
+    dir_sizes
+        .into_iter()
+        .filter(|size| *size >= needed_space)
+        .min()
+        .ok_or_else(|| {
+            AocError::unsolvable(
+                Day7::DAY,
+                "No directory is large enough to free the required space.",
+            )
         })
-        .sorted_by(|(_, size_a), (_, size_b)| Ord::cmp(size_a, &size_b))
-        .collect();
-
-    // Final dir is root dir.
-    let available_space = DISK_SIZE.saturating_sub(dir_sizes.last().unwrap().1);
-
-    let mut del_dir_size: Option<usize> = None;
-    for (_, size) in dir_sizes.iter() {
-        let space_after_del = available_space + *size;
-        if space_after_del > REQ_DISK_SPACE {
-            // println!("{:?} - {} ({})", dir, size, space_after_del);
-            del_dir_size = Some(*size);
-            break;
-        }
+}
+
+pub fn sum_file_system(fname: &str) -> Result<usize, AocError> {
+    let file_system = FileSystem::new(fname)?;
+    sum_small_dirs(&file_system)
+}
+
+pub fn free_space_file_system(fname: &str) -> Result<usize, AocError> {
+    let file_system = FileSystem::new(fname)?;
+    smallest_dir_to_free(&file_system)
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+    type Input = FileSystem;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        FileSystem::from_str(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(sum_small_dirs(input)?.into())
     }
 
-    Ok(del_dir_size.unwrap())
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(smallest_dir_to_free(input)?.into())
+    }
+}
+
+#[test]
+fn test_size_overflow() {
+    let file_system = FileSystem::from_str(&format!(
+        "$ cd /\n$ ls\ndir a\n1 b.txt\n$ cd a\n$ ls\n{} c.txt\n",
+        usize::MAX
+    ))
+    .unwrap();
+    assert_eq!(file_system.dirs()[1].du().unwrap(), usize::MAX);
+    assert!(matches!(
+        sum_small_dirs(&file_system),
+        Err(AocError::Unsolvable { day: 7, .. })
+    ));
+}
+
+#[test]
+fn test_deep_directories() {
+    const DEPTH: usize = 100_000;
+    let transcript = format!("$ cd /\n{}", "$ ls\ndir d\n1 f\n$ cd d\n".repeat(DEPTH));
+    let file_system = FileSystem::from_str(&transcript).unwrap();
+    assert_eq!(file_system.dirs().len(), DEPTH + 1);
+    assert_eq!(file_system.dirs()[0].du().unwrap(), DEPTH);
+
+    let sizes = file_system.dir_sizes().unwrap();
+    assert_eq!(sizes.iter().rev().take(3).collect_vec(), [&0, &1, &2]);
+    assert_eq!(
+        sum_small_dirs(&file_system).unwrap(),
+        (0..=100000).sum::<usize>()
+    );
+
+    // Dropping the root last still tears the tree down without recursing.
+    let root = file_system.dirs()[0].clone();
+    drop(file_system);
+    drop(root);
+}
+
+#[test]
+fn test_files_larger_than_disk() {
+    // 80000000 used, so 40000000 must be freed and b's 35000000 isn't enough.
+    let file_system = FileSystem::from_str(
+        "$ cd /\n$ ls\ndir a\ndir b\n$ cd a\n$ ls\n45000000 c\n$ cd ..\n$ cd b\n$ ls\n35000000 d\n",
+    )
+    .unwrap();
+    assert_eq!(smallest_dir_to_free(&file_system).unwrap(), 45_000_000);
 }
@@ -1,13 +1,37 @@
-use std::{error::Error, fs};
-
-use crate::days::common::GridString;
+use std::{fs::File, io::Read};
+
+use crate::days::{
+    common::{read_input, Grid},
+    error::AocError,
+    point::{Direction, Point},
+    solution::{Answer, Solution},
+};
 use itertools::Itertools;
 
-pub fn tree_top_visibility(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
-    // TODO: Use 2D list. No matrices.
+/// Parse a grid of tree heights from 0 to 9.
+pub fn parse_forest(contents: &str) -> Result<Grid<u32>, AocError> {
+    Grid::parse(contents, "Expected a tree height from 0 to 9.", |tree| {
+        tree.to_digit(10)
+    })
+    .map_err(|err| err.with_day(Day8::DAY))
+}
+
+pub fn read_forest(reader: impl Read) -> Result<Grid<u32>, AocError> {
+    parse_forest(&read_input(reader)?)
+}
+
+/// Heights of the trees in each direction from a tree up to the edge of the forest, nearest first.
+fn lines_of_sight(forest: &Grid<u32>, pos: (usize, usize)) -> [Vec<u32>; 4] {
+    Direction::ALL.map(|direction| {
+        Point::from_grid_index(pos)
+            .walk(direction)
+            .map_while(|point| forest.get(forest.index_of(point)?))
+            .copied()
+            .collect_vec()
+    })
+}
 
-    let forest = GridString::new(&contents)?;
+pub fn count_visible_trees(forest: &Grid<u32>) -> usize {
     /*
           01234
           |||||
@@ -19,74 +43,15 @@ pub fn tree_top_visibility(fname: &str) -> Result<usize, Box<dyn Error>> {
 
     */
 
-    // Get all tree coordinates on edges.
-    let mut visible_trees: usize = 0;
-    let mut edge_trees: Vec<(usize, usize)> = (0..forest.cols)
-        .cartesian_product([0, forest.rows - 1])
-        .collect_vec();
-    edge_trees.extend(
-        [0, forest.rows - 1]
-            .into_iter()
-            .cartesian_product(0..forest.cols)
-            .collect_vec(),
-    );
-
-    // Remove duplicated trees in corners from cartesian product.
-    visible_trees += edge_trees.len().saturating_sub(4);
-
-    for (i, tree) in forest.grid.chars().enumerate() {
-        let coords = (i / forest.cols, i % forest.cols);
-        // Ignore tree if on edge.
-        if !edge_trees.contains(&coords) {
-            if let (Some(tree_height), Some(trees_col), Some(trees_row)) = (
-                tree.to_digit(10),
-                forest.get(None, Some(coords.1)),
-                forest.get(Some(coords.0), None),
-            ) {
-                // Convert characters of digits to values.
-                let mut tree_col_heights = trees_col
-                    .into_iter()
-                    .map(|tree| tree.to_digit(10))
-                    .collect::<Option<Vec<u32>>>()
-                    .expect("Can't coerce value in col.");
-                let mut tree_row_heights = trees_row
-                    .into_iter()
-                    .map(|tree| tree.to_digit(10))
-                    .collect::<Option<Vec<u32>>>()
-                    .expect("Can't coerce value in col.");
-
-                // Remove compared tree.
-                tree_col_heights.remove(coords.0);
-                tree_row_heights.remove(coords.1);
-
-                // Split tree along axis at compared tree idx.
-                let l_r_trees_cols = tree_col_heights.split_at(coords.0);
-                let l_r_trees_rows = tree_row_heights.split_at(coords.1);
-
-                // Find max tree height within split axis.
-                let l_r_trees_cols_max = [
-                    l_r_trees_cols.0.iter().max().unwrap(),
-                    l_r_trees_cols.1.iter().max().unwrap(),
-                ];
-                let l_r_trees_rows_max = [
-                    l_r_trees_rows.0.iter().max().unwrap(),
-                    l_r_trees_rows.1.iter().max().unwrap(),
-                ];
-
-                // Find any tree where along split axis, any tree is shorter than it.
-                if [l_r_trees_cols_max, l_r_trees_rows_max]
-                    .concat()
-                    .iter()
-                    .any(|tree| **tree < tree_height)
-                {
-                    // println!("{tree_height} {:?} - col {:?} row {:?}", coords, l_r_trees_cols_max, l_r_trees_rows_max);
-                    visible_trees += 1;
-                }
-            }
-        }
-    }
-
-    Ok(visible_trees)
+    // A tree is visible if every tree along any line of sight is shorter. Trees on the edge have an empty line of sight.
+    forest
+        .iter()
+        .filter(|(coords, tree_height)| {
+            lines_of_sight(forest, *coords)
+                .iter()
+                .any(|trees| trees.iter().all(|tree| tree < tree_height))
+        })
+        .count()
 }
 
 fn tree_view_dst(tree_ht: u32, trees_along_axis: &[u32]) -> usize {
@@ -102,55 +67,44 @@ fn tree_view_dst(tree_ht: u32, trees_along_axis: &[u32]) -> usize {
     vis_trees
 }
 
-pub fn tree_scenic_scores(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
-    // TODO: Use 2D list. No matrices.
-
-    let forest = GridString::new(&contents)?;
-    let mut scenery_scores: Vec<usize> = vec![];
-
-    // TODO: Lazy and didn't remove edge trees. Would improve time.
-    for (i, tree) in forest.grid.chars().enumerate() {
-        let coords = (i / forest.cols, i % forest.cols);
-        if let (Some(tree_height), Some(trees_col), Some(trees_row)) = (
-            tree.to_digit(10),
-            forest.get(None, Some(coords.1)),
-            forest.get(Some(coords.0), None),
-        ) {
-            // Convert characters of digits to values.
-            let mut tree_col_heights = trees_col
-                .into_iter()
-                .map(|tree| tree.to_digit(10))
-                .collect::<Option<Vec<u32>>>()
-                .expect("Can't coerce value in col.");
-            let mut tree_row_heights = trees_row
-                .into_iter()
-                .map(|tree| tree.to_digit(10))
-                .collect::<Option<Vec<u32>>>()
-                .expect("Can't coerce value in col.");
-
-            // Remove compared tree.
-            tree_col_heights.remove(coords.0);
-            tree_row_heights.remove(coords.1);
-
-            // Split tree along axis at compared tree idx.
-            let (l_tree_cols, r_tree_cols) = tree_col_heights.split_at(coords.0);
-            let (l_tree_rows, r_tree_rows) = tree_row_heights.split_at(coords.1);
-
-            // TODO: Unnecessary clone. Can do calculate and assign to 4 vars.
-            // Reverse direction and order trees come in respective to compared tree.
-            let u_trees_cols = l_tree_cols.iter().rev().cloned().collect_vec();
-            let l_tree_rows = l_tree_rows.iter().rev().cloned().collect_vec();
-
-            let tree_views = [&u_trees_cols, r_tree_cols, &l_tree_rows, r_tree_rows]
+pub fn max_scenic_score(forest: &Grid<u32>) -> usize {
+    forest
+        .iter()
+        .map(|(coords, tree_height)| {
+            lines_of_sight(forest, coords)
                 .iter()
-                .map(|adj_trees| tree_view_dst(tree_height, &adj_trees))
-                .collect_vec();
+                .map(|adj_trees| tree_view_dst(*tree_height, adj_trees))
+                .product::<usize>()
+        })
+        .max()
+        .unwrap_or(0)
+}
 
-            // println!("{tree_height} {:?} -> {:?}", coords, tree_views);
-            scenery_scores.push(tree_views.iter().product())
-        }
+pub fn tree_top_visibility(fname: &str) -> Result<usize, AocError> {
+    let forest = read_forest(File::open(fname)?)?;
+    Ok(count_visible_trees(&forest))
+}
+
+pub fn tree_scenic_scores(fname: &str) -> Result<usize, AocError> {
+    let forest = read_forest(File::open(fname)?)?;
+    Ok(max_scenic_score(&forest))
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+    type Input = Grid<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_forest(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(count_visible_trees(input).into())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(max_scenic_score(input).into())
     }
-    let max_scenery_score = scenery_scores.iter().max().unwrap();
-    Ok(*max_scenery_score)
 }
@@ -1,293 +1,228 @@
-use crate::days::error::ParserError;
+use crate::days::{
+    common::read_input,
+    error::AocError,
+    input,
+    point::{Direction, Point},
+    solution::{Answer, Solution},
+};
 use itertools::Itertools;
 use std::{
-    cmp,
     collections::{HashSet, VecDeque},
-    error::Error,
-    fs,
-    ops::{Add, AddAssign, Range, Sub},
-    str::FromStr,
+    fs::File,
+    io::Read,
+    ops::Range,
     vec,
 };
-
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Position {
-    x: isize,
-    y: isize,
-}
-
-impl Position {
-    fn is_adj(&self, other: &Position) -> bool {
-        let dx = self.x.abs_diff(other.x);
-        let dy = self.y.abs_diff(other.y);
-        if dx <= 1 && dy <= 1 {
-            true
-        } else {
-            false
-        }
-    }
-
-    fn is_on_axis(&self, other: &Position) -> bool {
-        if self.x == other.x || self.y == other.y {
-            true
-        } else {
-            false
-        }
-    }
-
-    // https://www.reddit.com/r/adventofcode/comments/zgnice/2022_day_9_solutions/izugdyl/
-    fn compute_distance(self, to: Position) -> u32 {
-        let delta_x: u32 = (to.x - self.x).abs() as u32;
-        let delta_y: u32 = (to.y - self.y).abs() as u32;
-        return cmp::max(delta_x, delta_y);
-    }
-
-    fn compute_tail_move(self, to: Position) -> Position {
-        let mut delta_x = to.x - self.x;
-        let mut delta_y = to.y - self.y;
-        if (delta_x.abs() <= 2) && (delta_y.abs() <= 2) {
-            delta_x = delta_x.clamp(-1, 1);
-            delta_y = delta_y.clamp(-1, 1);
-        } else if delta_x.abs() == 2 && delta_y == 0 {
-            delta_x = delta_x.clamp(-1, 1);
-        } else if delta_x == 0 && delta_y.abs() == 2 {
-            delta_y = delta_y.clamp(-1, 1);
-        }
-        Position {
-            x: delta_x,
-            y: delta_y,
-        }
-    }
-}
-
-impl Add for Position {
-    type Output = Position;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Position {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-impl Sub for Position {
-    type Output = Position;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Position {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
-    }
-}
-
-impl AddAssign for Position {
-    fn add_assign(&mut self, rhs: Self) {
-        self.x = self.x + rhs.x;
-        self.y = self.y + rhs.y;
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-enum Direction {
-    U,
-    D,
-    L,
-    R,
-}
-
-impl From<Direction> for Position {
-    fn from(value: Direction) -> Self {
-        match value {
-            Direction::U => Position { x: 0, y: 1 },
-            Direction::D => Position { x: 0, y: -1 },
-            Direction::L => Position { x: -1, y: 0 },
-            Direction::R => Position { x: 1, y: 0 },
-        }
-    }
-}
-
-impl FromStr for Direction {
-    type Err = ParserError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "R" => Ok(Direction::R),
-            "L" => Ok(Direction::L),
-            "U" => Ok(Direction::U),
-            "D" => Ok(Direction::D),
-            _ => Err(ParserError {
-                reason: "Not a valid direction.".to_string(),
-            }),
-        }
+use tracing::trace;
+
+/// Parse a motion's direction: `R`, `L`, `U` or `D`.
+fn parse_direction(s: &str) -> Option<Direction> {
+    match s {
+        "R" => Some(Direction::Right),
+        "L" => Some(Direction::Left),
+        "U" => Some(Direction::Up),
+        "D" => Some(Direction::Down),
+        _ => None,
     }
 }
 
 #[derive(Debug)]
-struct Move {
-    pos: Position,
-    pos_change: Position,
-    segment: usize,
+pub struct Move {
+    pub pos: Point,
+    pub pos_change: Point,
+    pub segment: usize,
 }
 
 #[derive(Debug)]
-struct Rope {
-    segments: VecDeque<Position>,
+pub struct Rope {
+    segments: VecDeque<Point>,
     moves: Vec<Move>,
 }
 
 impl Rope {
-    fn new(segments: usize) -> Self {
+    pub fn new(segments: usize) -> Self {
         Rope {
-            segments: VecDeque::from_iter(vec![Position { x: 0, y: 0 }; segments]),
+            segments: VecDeque::from_iter(vec![Point::ORIGIN; segments]),
             moves: vec![],
         }
     }
 
     /// Move a `Rope` some `Direction` step-wise.
-    fn move_rope(&mut self, direction: Direction) {
-        let mut pos_changes = vec![];
-        let pos_change = Position::from(direction);
-
-        for (i, segment) in self.segments.iter().enumerate() {
-            // Head of rope.
-            if i == 0 {
-                pos_changes.push(pos_change);
-            } else {
-                if let (Some(prev_segment), Some(prev_segment_change)) =
-                    (self.segments.get(i - 1), pos_changes.get(i - 1))
-                {
-                    if segment.compute_distance(*prev_segment) > 1 {
-                        let adj_pos_change = segment.compute_tail_move(*prev_segment);
-                        pos_changes.push(adj_pos_change);
-                    } else {
-                        pos_changes.push(Position { x: 0, y: 0 })
-                    }
-
-                    //     let new_prev_segment_pos = *prev_segment + *prev_segment_change;
-
-                    //     // If directly adjacent to previous segment.
-                    //     if segment.is_adj(&new_prev_segment_pos) {
-                    //         continue;
-                    //         // pos_changes.push(Position { x: 0, y: 0 })
-                    //     } else if !segment.is_adj(&new_prev_segment_pos)
-                    //         && !segment.is_on_axis(&new_prev_segment_pos)
-                    //     {
-
-                    //     } else {
-                    //         pos_changes.push(pos_change)
-                    //     }
-                    // }
+    pub fn move_rope(&mut self, direction: Direction) {
+        let mut prev_segment: Option<Point> = None;
+
+        for (i, segment) in self.segments.iter_mut().enumerate() {
+            let pos_change = if let Some(prev_segment) = prev_segment {
+                // Follow the previous segment's already updated position, one step along each axis at most.
+                // https://www.reddit.com/r/adventofcode/comments/zgnice/2022_day_9_solutions/izugdyl/
+                if segment.chebyshev(prev_segment) > 1 {
+                    (prev_segment - *segment).signum()
+                } else {
+                    Point::ORIGIN
                 }
-            }
-        }
-        for (i, (curr_pos, pos_change)) in self.segments.iter_mut().zip(pos_changes).enumerate() {
-            *curr_pos += pos_change;
-            println!("{i} Moved {:?} to {:?}", pos_change, curr_pos);
+            } else {
+                // Head of rope.
+                Point::from(direction)
+            };
+            *segment += pos_change;
+            trace!("{i} Moved {pos_change:?} to {segment:?}");
             self.moves.push(Move {
-                pos: *curr_pos,
+                pos: *segment,
                 pos_change,
                 segment: i,
-            })
+            });
+            prev_segment = Some(*segment);
         }
     }
 
+    /// Current position of each segment, starting with the head.
+    pub fn segments(&self) -> &VecDeque<Point> {
+        &self.segments
+    }
+
+    /// Every move made by every segment.
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
     /// Produce a grid showing the positions that a specific rope segment(s) visited.
     ///
     /// **Warning**: Very memory expensive as will produce sparse grid.
-    /// ```
+    /// ```text
     /// ..##..
     /// ...##.
     /// .####.
     /// ....#.
     /// _###..
     /// ```
-    fn visited_positions(&self, rope_segments: Range<usize>) -> String {
-        let mut grid: Vec<Vec<char>> = vec![];
-        let mut cols: usize = 0;
-        let mut rows: usize = 0;
-
-        // Get bounds.
-        for mv in self.moves.iter() {
-            let (x, y) = (mv.pos.x as usize, mv.pos.y as usize);
-            if x > cols {
-                cols = x
-            }
-            if y > rows {
-                rows = y
-            }
-        }
-        // Fill out rows
-        for _ in 0..=rows {
-            grid.push(vec![])
-        }
-        // Fill empty spaces in grid. Fill in rope elements.
-        for mv in self.moves.iter() {
-            let (_, row) = (mv.pos.x as usize, mv.pos.y as usize);
-
-            let spec_row = grid.get_mut(row).unwrap();
-            while spec_row.len() <= cols {
-                spec_row.push('.')
-            }
-            if !rope_segments.contains(&mv.segment) {
-                continue;
-            }
-            // Should never panic.
-            grid[mv.pos.y as usize][mv.pos.x as usize] = '#'
-        }
-        grid.iter()
-            .rev()
-            .map(|cols| cols.iter().join(""))
+    pub fn visited_positions(&self, rope_segments: Range<usize>) -> String {
+        let visited: HashSet<Point> = self
+            .moves
+            .iter()
+            .filter(|mv| rope_segments.contains(&mv.segment))
+            .map(|mv| mv.pos)
+            .collect();
+        // Bounds of every segment's moves, not only the visited positions.
+        let (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) = (
+            self.moves.iter().map(|mv| mv.pos.x).min(),
+            self.moves.iter().map(|mv| mv.pos.x).max(),
+            self.moves.iter().map(|mv| mv.pos.y).min(),
+            self.moves.iter().map(|mv| mv.pos.y).max(),
+        ) else {
+            return String::new();
+        };
+
+        (min_y..=max_y)
+            .map(|y| {
+                (min_x..=max_x)
+                    .map(|x| {
+                        if visited.contains(&Point::new(x, y)) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
             .join("\n")
     }
 }
 
-pub fn rope_movement(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
+pub fn parse_motions(contents: &str) -> Result<Vec<(Direction, usize)>, AocError> {
+    let mut motions = vec![];
+    for (i, instruction) in input::non_blank_lines(contents) {
+        let Some((direction, steps)) = instruction.split_whitespace().collect_tuple() else {
+            return Err(AocError::parse(
+                Day9::DAY,
+                instruction,
+                "Expected a direction and a number of steps like 'R 4'.",
+            )
+            .offset_lines(i));
+        };
+        let direction = parse_direction(direction).ok_or_else(|| {
+            AocError::parse_at(
+                Day9::DAY,
+                instruction,
+                direction,
+                "Expected a direction (R, L, U or D).",
+            )
+            .offset_lines(i)
+        })?;
+        let steps = steps.parse::<usize>().map_err(|_| {
+            AocError::parse_at(Day9::DAY, instruction, steps, "Expected a number of steps.")
+                .offset_lines(i)
+        })?;
+        motions.push((direction, steps))
+    }
+    Ok(motions)
+}
 
-    let segment_len: usize = 10;
+/// Number of unique positions visited by the tail of a rope with some number of segments, including where it starts.
+pub fn uniq_tail_positions(
+    motions: &[(Direction, usize)],
+    segment_len: usize,
+) -> Result<usize, AocError> {
+    let Some(tail) = segment_len.checked_sub(1) else {
+        return Err(AocError::unsolvable(
+            Day9::DAY,
+            "A rope needs at least one segment.",
+        ));
+    };
     let mut rope = Rope::new(segment_len);
 
-    for instruction in contents.lines() {
-        if let Some((direction, steps)) = instruction.splitn(2, " ").collect_tuple() {
-            let movement_direction = Direction::from_str(direction)?;
-
-            // Move step wise from instructions.
-            for _ in 0..steps.parse::<usize>()? {
-                rope.move_rope(movement_direction)
-            }
+    for (movement_direction, steps) in motions.iter() {
+        // Move step wise from instructions.
+        for _ in 0..*steps {
+            rope.move_rope(*movement_direction)
         }
     }
-    println!(
-        "{:?}",
-        rope.moves
-            .iter()
-            .filter_map(|mv| if mv.segment == segment_len - 1 {
-                Some(mv.pos)
-            } else {
-                None
-            })
-            .collect_vec()
-    );
-    // Get the second element in rope (ie. the tail).
-    let uniq_tail_pos: HashSet<Position> = rope
-        .moves
-        .iter()
-        .filter_map(|mv| {
-            if mv.segment == segment_len - 1 {
-                Some(mv.pos)
-            } else {
-                None
-            }
-        })
+    // Get the last element in rope (ie. the tail), which starts at the origin.
+    let uniq_tail_pos: HashSet<Point> = std::iter::once(Point::ORIGIN)
+        .chain(
+            rope.moves
+                .iter()
+                .filter(|mv| mv.segment == tail)
+                .map(|mv| mv.pos),
+        )
         .collect();
-
-    // // // View grid.
-    // let grid_pos = rope.visited_positions(Range { start: 1, end: 2 });
-    // let grid_char_cnts = grid_pos.chars().counts();
-    // let visited_tail_pos = grid_char_cnts.get(&'#').unwrap_or(&0);
-    // Ok(*visited_tail_pos)
+    trace!("Tail positions: {uniq_tail_pos:?}");
 
     Ok(uniq_tail_pos.len())
 }
+
+pub fn read_motions(reader: impl Read) -> Result<Vec<(Direction, usize)>, AocError> {
+    parse_motions(&read_input(reader)?)
+}
+
+pub fn rope_movement(fname: &str) -> Result<usize, AocError> {
+    uniq_tail_positions(&read_motions(File::open(fname)?)?, 10)
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+    type Input = Vec<(Direction, usize)>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse_motions(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(uniq_tail_positions(input, 2)?.into())
+    }
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError> {
+        Ok(uniq_tail_positions(input, 10)?.into())
+    }
+}
+
+#[test]
+fn test_tail_start_and_zero_segments() {
+    let motions = parse_motions("R 0").unwrap();
+    assert_eq!(uniq_tail_positions(&motions, 2).unwrap(), 1);
+    assert_eq!(uniq_tail_positions(&[], 10).unwrap(), 1);
+
+    let err = uniq_tail_positions(&motions, 0).unwrap_err();
+    assert_eq!(err.to_string(), "Day 9: A rope needs at least one segment.");
+}
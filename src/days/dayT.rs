@@ -1,10 +1,20 @@
-use std::error::Error;
-use std::fs;
+use std::fs::File;
+use std::io::Read;
 
 use itertools::Itertools;
 
-pub fn template(fname: &str) -> Result<usize, Box<dyn Error>> {
-    let contents = fs::read_to_string(fname)?;
+use crate::days::{common::read_input, error::AocError};
+
+pub fn parse_template(contents: &str) -> Result<Vec<String>, AocError> {
+    Ok(contents.lines().map(String::from).collect_vec())
+}
+
+pub fn read_template(reader: impl Read) -> Result<Vec<String>, AocError> {
+    parse_template(&read_input(reader)?)
+}
+
+pub fn template(fname: &str) -> Result<usize, AocError> {
+    let contents = read_template(File::open(fname)?)?;
 
     Ok(0)
-}
\ No newline at end of file
+}
@@ -1,11 +1,149 @@
+use std::{fmt, io};
+
+/// Error from reading, parsing or solving a day's puzzle input.
 #[derive(Debug)]
-pub struct ParserError {
-    pub reason: String,
+pub enum AocError {
+    /// Input couldn't be read.
+    Io(io::Error),
+    /// Malformed input.
+    /// * `line` and `column` start at 1 and point to the start of `text`.
+    /// * `day` is unknown for errors from shared parsers, like `Grid`, until the day's parser fills it in.
+    Parse {
+        day: Option<u8>,
+        line: usize,
+        column: usize,
+        text: String,
+        reason: String,
+    },
+    /// Well-formed input that doesn't have an answer.
+    Unsolvable { day: u8, reason: String },
+}
+
+impl AocError {
+    /// Malformed line of a day's input. The error is on line 1 until moved with `offset_lines`.
+    pub fn parse(day: u8, line: &str, reason: impl Into<String>) -> Self {
+        AocError::Parse {
+            day: Some(day),
+            line: 1,
+            column: 1,
+            text: line.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Malformed `token` within a line of a day's input.
+    /// * `token` must be a slice of `line` so that its column can be found. Otherwise, column 1 is used.
+    pub fn parse_at(day: u8, line: &str, token: &str, reason: impl Into<String>) -> Self {
+        AocError::Parse {
+            day: Some(day),
+            line: 1,
+            column: column_of(line, token),
+            text: token.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn unsolvable(day: u8, reason: impl Into<String>) -> Self {
+        AocError::Unsolvable {
+            day,
+            reason: reason.into(),
+        }
+    }
+
+    /// Move a parse error from a line or block of input to `n_lines` further into the input.
+    pub fn offset_lines(self, n_lines: usize) -> Self {
+        match self {
+            AocError::Parse {
+                day,
+                line,
+                column,
+                text,
+                reason,
+            } => AocError::Parse {
+                day,
+                line: line + n_lines,
+                column,
+                text,
+                reason,
+            },
+            err => err,
+        }
+    }
+
+    /// Set the day of a parse error from a shared parser if it isn't already known.
+    pub fn with_day(self, day: u8) -> Self {
+        match self {
+            AocError::Parse {
+                day: None,
+                line,
+                column,
+                text,
+                reason,
+            } => AocError::Parse {
+                day: Some(day),
+                line,
+                column,
+                text,
+                reason,
+            },
+            err => err,
+        }
+    }
 }
 
-impl std::fmt::Display for ParserError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Unable to parse prompt. {}", self.reason)
+/// Column, starting at 1, of a `token` sliced from a `line`.
+fn column_of(line: &str, token: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let token_start = token.as_ptr() as usize;
+    if (start..=start + line.len()).contains(&token_start) {
+        line[..token_start - start].chars().count() + 1
+    } else {
+        1
     }
 }
-impl std::error::Error for ParserError {}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::Io(err) => write!(f, "Can't read input. {err}"),
+            AocError::Parse {
+                day,
+                line,
+                column,
+                text,
+                reason,
+            } => {
+                if let Some(day) = day {
+                    write!(f, "Day {day}, ")?;
+                }
+                write!(f, "line {line}, column {column}: {reason} Found '{text}'.")
+            }
+            AocError::Unsolvable { day, reason } => write!(f, "Day {day}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AocError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(value: io::Error) -> Self {
+        AocError::Io(value)
+    }
+}
+
+#[test]
+fn test_parse_error_location() {
+    let line = "2-4,6-x";
+    let err = AocError::parse_at(4, line, &line[6..], "Expected a section number.").offset_lines(2);
+    assert_eq!(
+        err.to_string(),
+        "Day 4, line 3, column 7: Expected a section number. Found 'x'."
+    );
+}
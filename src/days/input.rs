@@ -0,0 +1,88 @@
+//! Splitting of puzzle input shared by every day so that LF and CRLF inputs give identical answers.
+
+/// Lines of input numbered from 0. Line endings (`\n` or `\r\n`) and trailing whitespace are removed.
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.lines().map(str::trim_end).enumerate()
+}
+
+/// Lines of input that aren't blank, numbered from 0.
+pub fn non_blank_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    lines(input).filter(|(_, line)| !line.is_empty())
+}
+
+/// Consecutive lines of input that aren't blank.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block<'a> {
+    /// Line number, from 0, of the block's first line.
+    pub start: usize,
+    pub lines: Vec<&'a str>,
+}
+
+impl<'a> Block<'a> {
+    /// Lines of the block numbered from the start of the input.
+    pub fn numbered_lines(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        (self.start..).zip(self.lines.iter().copied())
+    }
+}
+
+/// Blocks of lines separated by one or more blank lines.
+pub fn blocks(input: &str) -> Vec<Block<'_>> {
+    let mut blocks: Vec<Block> = vec![];
+    let mut in_block = false;
+
+    for (i, line) in lines(input) {
+        if line.is_empty() {
+            in_block = false;
+            continue;
+        }
+        match blocks.last_mut() {
+            Some(block) if in_block => block.lines.push(line),
+            _ => blocks.push(Block {
+                start: i,
+                lines: vec![line],
+            }),
+        }
+        in_block = true;
+    }
+    blocks
+}
+
+#[test]
+fn test_crlf_blocks() {
+    let lf = "1000\n2000\n\n\n3000\n";
+    let crlf = lf.replace('\n', "\r\n");
+    let expected = [
+        Block {
+            start: 0,
+            lines: vec!["1000", "2000"],
+        },
+        Block {
+            start: 4,
+            lines: vec!["3000"],
+        },
+    ];
+    assert_eq!(blocks(lf), expected);
+    assert_eq!(blocks(&crlf), expected);
+}
+
+#[test]
+fn test_crlf_answers() {
+    use crate::days::{registry::PUZZLES, solution::Part};
+    use std::fs;
+
+    for puzzle in PUZZLES.iter() {
+        let Ok(lf) = fs::read_to_string(format!("data/test_day_{}_1.txt", puzzle.day)) else {
+            continue;
+        };
+        let crlf = lf.replace('\n', "\r\n");
+        let (lf_solver, crlf_solver) = (puzzle.parse(&lf).unwrap(), puzzle.parse(&crlf).unwrap());
+        for part in Part::ALL {
+            assert_eq!(
+                lf_solver.solve(part).unwrap(),
+                crlf_solver.solve(part).unwrap(),
+                "Day {} Part {part}",
+                puzzle.day
+            );
+        }
+    }
+}
@@ -13,3 +13,7 @@ pub mod day7;
 pub mod day8;
 pub mod day9;
 pub mod error;
+pub mod input;
+pub mod point;
+pub mod registry;
+pub mod solution;
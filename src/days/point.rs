@@ -0,0 +1,178 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Signed 2D point or vector. `y` grows downward so that points line up with grid rows.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: isize, y: isize) -> Self {
+        Point { x, y }
+    }
+
+    /// Distance moving only along the axes.
+    pub fn manhattan(self, other: Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Distance moving along the axes or diagonally, like a king in chess.
+    pub fn chebyshev(self, other: Point) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Vector with each component reduced to -1, 0 or 1.
+    pub fn signum(self) -> Point {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+
+    /// Point one step in a direction.
+    pub fn step(self, direction: Direction) -> Point {
+        self + Point::from(direction)
+    }
+
+    /// Points stepping away in a direction, starting with the nearest.
+    pub fn walk(self, direction: Direction) -> impl Iterator<Item = Point> {
+        (1..).map(move |n| self + Point::from(direction) * n)
+    }
+
+    /// Neighbours that share an edge, clockwise from above.
+    pub fn neighbours_4(self) -> impl Iterator<Item = Point> {
+        Direction::ALL
+            .into_iter()
+            .map(move |direction| self.step(direction))
+    }
+
+    /// Neighbours including diagonals, clockwise from above.
+    pub fn neighbours_8(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.into_iter().flat_map(move |direction| {
+            let next = self.step(direction);
+            [next, next.step(direction.rotate_right())]
+        })
+    }
+
+    /// Point of a `(row, col)` grid index.
+    pub fn from_grid_index((row, col): (usize, usize)) -> Point {
+        Point::new(col as isize, row as isize)
+    }
+
+    /// `(row, col)` grid index of a point. `None` if either coordinate is negative.
+    pub fn grid_index(self) -> Option<(usize, usize)> {
+        Some((usize::try_from(self.y).ok()?, usize::try_from(self.x).ok()?))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Self::Output {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Every direction clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Direction after a quarter turn clockwise.
+    pub fn rotate_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    /// Direction after a quarter turn counterclockwise.
+    pub fn rotate_left(self) -> Direction {
+        self.rotate_right().opposite()
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.rotate_right().rotate_right()
+    }
+}
+
+/// Unit vector of a direction.
+impl From<Direction> for Point {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+}
+
+#[test]
+fn test_point_algebra() {
+    let point = Point::new(2, -3);
+    assert_eq!(point.step(Direction::Up), Point::new(2, -4));
+    assert_eq!(point - Point::new(5, 1), Point::new(-3, -4));
+    assert_eq!((point * 3).signum(), Point::new(1, -1));
+    assert_eq!(point.manhattan(Point::ORIGIN), 5);
+    assert_eq!(point.chebyshev(Point::ORIGIN), 3);
+    assert_eq!(Direction::Left.rotate_right(), Direction::Up);
+    assert_eq!(Direction::Left.rotate_left(), Direction::Down);
+    assert_eq!(
+        point.walk(Direction::Right).take(2).collect::<Vec<Point>>(),
+        [Point::new(3, -3), Point::new(4, -3)]
+    );
+    assert_eq!(point.neighbours_8().count(), 8);
+    assert_eq!(point.grid_index(), None);
+    assert_eq!(Point::from_grid_index((4, 1)), Point::new(1, 4));
+}
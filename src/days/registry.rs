@@ -0,0 +1,26 @@
+use crate::days::{
+    day1::Day1, day10::Day10, day11::Day11, day12::Day12, day13::Day13, day2::Day2, day3::Day3,
+    day4::Day4, day5::Day5, day6::Day6, day7::Day7, day8::Day8, day9::Day9, solution::Puzzle,
+};
+
+/// Every solved day in order.
+pub const PUZZLES: [Puzzle; 13] = [
+    Puzzle::of::<Day1>(),
+    Puzzle::of::<Day2>(),
+    Puzzle::of::<Day3>(),
+    Puzzle::of::<Day4>(),
+    Puzzle::of::<Day5>(),
+    Puzzle::of::<Day6>(),
+    Puzzle::of::<Day7>(),
+    Puzzle::of::<Day8>(),
+    Puzzle::of::<Day9>(),
+    Puzzle::of::<Day10>(),
+    Puzzle::of::<Day11>(),
+    Puzzle::of::<Day12>(),
+    Puzzle::of::<Day13>(),
+];
+
+/// Get the puzzle for a given day.
+pub fn get(day: u8) -> Option<Puzzle> {
+    PUZZLES.into_iter().find(|puzzle| puzzle.day == day)
+}
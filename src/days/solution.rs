@@ -0,0 +1,164 @@
+use std::{fmt, fs::File, io::Read, marker::PhantomData};
+
+use serde::{Serialize, Serializer};
+
+use crate::days::{common::read_input, error::AocError};
+
+/// Answer to a single part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    /// Wide enough for any `usize` or `isize` answer.
+    Number(i128),
+    Text(String),
+    /// Rows of pixels, like a rendered screen, that spell out the answer.
+    Image(Vec<String>),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(num) => write!(f, "{num}"),
+            Answer::Text(text) => write!(f, "{text}"),
+            Answer::Image(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+/// Numbers are serialized as numbers and everything else as its displayed text.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Number(num) => serializer.serialize_i128(*num),
+            Answer::Text(text) => serializer.serialize_str(text),
+            Answer::Image(_) => serializer.collect_str(self),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Number(value as i128)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        Answer::Number(value as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One = 1,
+    Two = 2,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", *self as u8)
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(format!("Invalid part {value}. Must be 1 or 2.")),
+        }
+    }
+}
+
+/// A day's puzzle. The input is parsed once and then shared by both parts.
+pub trait Solution {
+    /// Day of the puzzle.
+    const DAY: u8;
+
+    /// Parsed puzzle input.
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError>;
+
+    /// Parse input from a reader, like stdin or an open file.
+    fn read(reader: impl Read) -> Result<Self::Input, AocError> {
+        Self::parse(&read_input(reader)?)
+    }
+
+    fn read_file(fname: &str) -> Result<Self::Input, AocError> {
+        Self::read(File::open(fname)?)
+    }
+
+    fn part_1(input: &Self::Input) -> Result<Answer, AocError>;
+
+    fn part_2(input: &Self::Input) -> Result<Answer, AocError>;
+}
+
+/// Parsed input for a day that can solve either part.
+pub trait Solver {
+    fn solve(&self, part: Part) -> Result<Answer, AocError>;
+}
+
+struct Parsed<S: Solution>(S::Input, PhantomData<S>);
+
+impl<S: Solution> Solver for Parsed<S> {
+    fn solve(&self, part: Part) -> Result<Answer, AocError> {
+        match part {
+            Part::One => S::part_1(&self.0),
+            Part::Two => S::part_2(&self.0),
+        }
+    }
+}
+
+type ParseFn = fn(&str) -> Result<Box<dyn Solver>, AocError>;
+
+fn parse_boxed<S: Solution + 'static>(input: &str) -> Result<Box<dyn Solver>, AocError> {
+    let input = S::parse(input).map_err(|err| err.with_day(S::DAY))?;
+    Ok(Box::new(Parsed::<S>(input, PhantomData)))
+}
+
+/// Type-erased `Solution` so days with different inputs can be stored together.
+#[derive(Debug, Clone, Copy)]
+pub struct Puzzle {
+    pub day: u8,
+    parse: ParseFn,
+}
+
+impl Puzzle {
+    pub const fn of<S: Solution + 'static>() -> Self {
+        Puzzle {
+            day: S::DAY,
+            parse: parse_boxed::<S>,
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Solver>, AocError> {
+        (self.parse)(input)
+    }
+
+    pub fn parts(&self) -> [Part; 2] {
+        Part::ALL
+    }
+}
+
+#[test]
+fn test_large_answers() {
+    let answer = Answer::from(usize::MAX);
+    assert_eq!(answer.to_string(), usize::MAX.to_string());
+    assert_eq!(
+        serde_json::to_string(&answer).unwrap(),
+        usize::MAX.to_string()
+    );
+    assert_eq!(Answer::from(isize::MIN).to_string(), isize::MIN.to_string());
+}
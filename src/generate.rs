@@ -0,0 +1,368 @@
+use std::ops::RangeInclusive;
+
+use itertools::Itertools;
+
+/// Days with an input generator.
+pub const DAYS: RangeInclusive<u8> = 1..=13;
+
+/// Seeded random number generator (SplitMix64) so that a seed always generates the same input.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Number in a range. The slight bias of the modulo doesn't matter for puzzle inputs.
+    fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let span = (range.end() - range.start()) as u64 + 1;
+        range.start() + (self.next_u64() % span) as usize
+    }
+
+    /// True one time in `n`.
+    fn one_in(&mut self, n: usize) -> bool {
+        self.range(1..=n) == 1
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.range(0..=items.len() - 1)]
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..=i));
+        }
+    }
+}
+
+/// Random, well-formed input for a day. `None` if the day has no generator.
+///
+/// What `size` counts depends on the day. Sizes below a day's minimum are raised to it.
+/// * Day 1: elves.
+/// * Day 2: rounds.
+/// * Day 3: rucksacks, rounded up to whole groups of three.
+/// * Day 4: pairs of elves.
+/// * Day 5: moves between nine stacks.
+/// * Day 6: characters in the buffer, at least 14.
+/// * Day 7: directories.
+/// * Day 8: rows and columns of the forest.
+/// * Day 9: motions of the rope's head.
+/// * Day 10: instructions.
+/// * Day 11: monkeys, at least 2.
+/// * Day 12: rows of the heightmap. It's as wide as it is tall but at least 26 columns wide.
+/// * Day 13: pairs of packets.
+pub fn generate(day: u8, size: usize, seed: u64) -> Option<String> {
+    let mut rng = Rng(seed);
+    let size = size.max(1);
+    let input = match day {
+        1 => calories(&mut rng, size),
+        2 => strategy_guide(&mut rng, size),
+        3 => rucksacks(&mut rng, size),
+        4 => section_pairs(&mut rng, size),
+        5 => supplies(&mut rng, size),
+        6 => datastream(&mut rng, size),
+        7 => terminal(&mut rng, size),
+        8 => forest(&mut rng, size),
+        9 => motions(&mut rng, size),
+        10 => program(&mut rng, size),
+        11 => monkeys(&mut rng, size),
+        12 => heightmap(&mut rng, size),
+        13 => packet_pairs(&mut rng, size),
+        _ => return None,
+    };
+    Some(input)
+}
+
+fn calories(rng: &mut Rng, n_elves: usize) -> String {
+    (0..n_elves)
+        .map(|_| {
+            (0..rng.range(1..=15))
+                .map(|_| format!("{}\n", rng.range(1_000..=60_000)))
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+fn strategy_guide(rng: &mut Rng, n_rounds: usize) -> String {
+    (0..n_rounds)
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                rng.pick(&['A', 'B', 'C']),
+                rng.pick(&['X', 'Y', 'Z'])
+            )
+        })
+        .collect()
+}
+
+/// Each rucksack's compartments share exactly one item and each group of three shares exactly one badge.
+fn rucksacks(rng: &mut Rng, n_sacks: usize) -> String {
+    let mut items = ('a'..='z').chain('A'..='Z').collect_vec();
+    let mut sacks = String::new();
+
+    for _ in 0..n_sacks.div_ceil(3) {
+        rng.shuffle(&mut items);
+        let (badge, rest) = (items[0], &items[1..]);
+        // Each sack of the group draws from its own items so only the badge is common to all three.
+        for own in rest.chunks(rest.len() / 3) {
+            let (shared, left, right) = (own[0], &own[1..9], &own[9..]);
+            let n_items = rng.range(0..=20);
+            let mut first = vec![shared, badge];
+            first.extend((0..n_items).map(|_| rng.pick(left)));
+            let mut second = vec![shared];
+            second.extend((0..=n_items).map(|_| rng.pick(right)));
+            rng.shuffle(&mut first);
+            rng.shuffle(&mut second);
+            sacks.extend(first.into_iter().chain(second));
+            sacks.push('\n');
+        }
+    }
+    sacks
+}
+
+fn section_pairs(rng: &mut Rng, n_pairs: usize) -> String {
+    let range = |rng: &mut Rng| {
+        let start = rng.range(1..=99);
+        format!("{start}-{}", rng.range(start..=99))
+    };
+    (0..n_pairs)
+        .map(|_| format!("{},{}\n", range(rng), range(rng)))
+        .collect()
+}
+
+/// Moves only take crates that are on the stack they're moved from.
+fn supplies(rng: &mut Rng, n_moves: usize) -> String {
+    const N_STACKS: usize = 9;
+    let stacks = (0..N_STACKS)
+        .map(|_| {
+            (0..rng.range(1..=8))
+                .map(|_| rng.range(0..=25) as u8 + b'A')
+                .map(char::from)
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut drawing = (0..height)
+        .rev()
+        .map(|level| {
+            stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(item) => format!("[{item}]"),
+                    None => "   ".to_string(),
+                })
+                .join(" ")
+        })
+        .collect_vec();
+    drawing.push((1..=N_STACKS).map(|num| format!(" {num} ")).join(" "));
+
+    let mut heights = stacks.iter().map(Vec::len).collect_vec();
+    let moves = (0..n_moves)
+        .map(|_| {
+            let from = rng.pick(&(0..N_STACKS).filter(|i| heights[*i] > 0).collect_vec());
+            let to = (from + rng.range(1..=N_STACKS - 1)) % N_STACKS;
+            let n_crates = rng.range(1..=heights[from].min(30));
+            heights[from] -= n_crates;
+            heights[to] += n_crates;
+            format!("move {n_crates} from {} to {}\n", from + 1, to + 1)
+        })
+        .collect::<String>();
+
+    format!("{}\n\n{moves}", drawing.join("\n"))
+}
+
+/// The start-of-packet marker is somewhere in the first half and the start-of-message marker is near the end.
+fn datastream(rng: &mut Rng, len: usize) -> String {
+    let len = len.max(14);
+    let mut letters = ('a'..='z').collect_vec();
+    rng.shuffle(&mut letters);
+    // Three letters can't make a marker and thirteen can only make a start-of-packet marker.
+    let packet_start = rng.range(0..=(len - 14) / 2);
+    let mut buffer = (0..packet_start)
+        .map(|_| rng.pick(&letters[..3]))
+        .collect::<String>();
+    buffer.extend((packet_start..len - 14).map(|_| rng.pick(&letters[..13])));
+    rng.shuffle(&mut letters);
+    buffer.extend(&letters[..14]);
+    buffer.push('\n');
+    buffer
+}
+
+/// Terminal output of exploring every directory of a random tree depth first.
+fn terminal(rng: &mut Rng, n_dirs: usize) -> String {
+    let mut children: Vec<Vec<usize>> = vec![vec![]; n_dirs];
+    // Mostly nest in the newest directory so that paths grow with the transcript, and sometimes
+    // climb a few levels of its path to branch off.
+    let mut newest_path = vec![0];
+    for dir in 1..n_dirs {
+        if rng.one_in(4) {
+            let climb = rng.range(1..=3).min(newest_path.len() - 1);
+            newest_path.truncate(newest_path.len() - climb);
+        }
+        if let Some(parent) = newest_path.last() {
+            children[*parent].push(dir);
+        }
+        newest_path.push(dir);
+    }
+
+    let listing = |rng: &mut Rng, dir: usize| {
+        let files = (0..rng.range(0..=4)).map(|i| {
+            let ext = rng.pick(&["", ".txt", ".dat", ".log", ".lst"]);
+            format!("{} f{i}{ext}\n", rng.range(1_000..=300_000))
+        });
+        let dirs = children[dir].iter().map(|child| format!("dir d{child}\n"));
+        format!("$ ls\n{}", dirs.chain(files).collect::<String>())
+    };
+
+    let mut transcript = format!("$ cd /\n{}", listing(rng, 0));
+    // Each directory on the current path and how many of its children have been explored.
+    let mut path = vec![(0, 0)];
+    while let Some((dir, n_explored)) = path.last_mut() {
+        match children[*dir].get(*n_explored).copied() {
+            Some(child) => {
+                *n_explored += 1;
+                transcript += &format!("$ cd d{child}\n{}", listing(rng, child));
+                path.push((child, 0));
+            }
+            None => {
+                path.pop();
+                if !path.is_empty() {
+                    transcript += "$ cd ..\n";
+                }
+            }
+        }
+    }
+    transcript
+}
+
+fn forest(rng: &mut Rng, side: usize) -> String {
+    (0..side)
+        .map(|_| {
+            let row = (0..side).map(|_| rng.range(0..=9)).join("");
+            format!("{row}\n")
+        })
+        .collect()
+}
+
+fn motions(rng: &mut Rng, n_motions: usize) -> String {
+    (0..n_motions)
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                rng.pick(&['U', 'D', 'L', 'R']),
+                rng.range(1..=20)
+            )
+        })
+        .collect()
+}
+
+fn program(rng: &mut Rng, n_instructions: usize) -> String {
+    (0..n_instructions)
+        .map(|_| {
+            if rng.one_in(3) {
+                "noop\n".to_string()
+            } else {
+                format!("addx {}\n", rng.range(0..=40) as isize - 20)
+            }
+        })
+        .collect()
+}
+
+/// Operations multiply by at most 3 so that worry levels never grow when they're divided by 3 after each
+/// inspection.
+fn monkeys(rng: &mut Rng, n_monkeys: usize) -> String {
+    let n_monkeys = n_monkeys.max(2);
+    (0..n_monkeys)
+        .map(|num| {
+            let items = (0..rng.range(1..=8)).map(|_| rng.range(50..=99)).join(", ");
+            let operation = if rng.one_in(2) {
+                format!("old + {}", rng.range(1..=8))
+            } else {
+                format!("old * {}", rng.range(2..=3))
+            };
+            let divisor = rng.pick(&[2, 3, 5, 7, 11, 13, 17, 19, 23]);
+            let other = |rng: &mut Rng| (num + rng.range(1..=n_monkeys - 1)) % n_monkeys;
+            let (on_true, on_false) = (other(rng), other(rng));
+            format!(
+                "Monkey {num}:\n  \
+                 Starting items: {items}\n  \
+                 Operation: new = {operation}\n  \
+                 Test: divisible by {divisor}\n    \
+                 If true: throw to monkey {on_true}\n    \
+                 If false: throw to monkey {on_false}\n"
+            )
+        })
+        .join("\n")
+}
+
+/// Elevation rises from `a` in the first column to `z` in the last. The start's row and the last column are
+/// never lowered, so there is always a path from the start to the end.
+fn heightmap(rng: &mut Rng, rows: usize) -> String {
+    let cols = rows.max(26);
+    let (start_row, end_row) = (rng.range(0..=rows - 1), rng.range(0..=rows - 1));
+    (0..rows)
+        .map(|row| {
+            let squares = (0..cols)
+                .map(|col| {
+                    let elevation = col * 25 / (cols - 1);
+                    let lowered = if row != start_row && col != cols - 1 && rng.one_in(3) {
+                        elevation.saturating_sub(rng.range(1..=3))
+                    } else {
+                        elevation
+                    };
+                    match (row, col) {
+                        (row, 0) if row == start_row => 'S',
+                        (row, col) if row == end_row && col == cols - 1 => 'E',
+                        _ => char::from(b'a' + lowered as u8),
+                    }
+                })
+                .collect::<String>();
+            format!("{squares}\n")
+        })
+        .collect()
+}
+
+fn packet(rng: &mut Rng, depth: usize) -> String {
+    let items = (0..rng.range(0..=5))
+        .map(|_| {
+            if depth < 4 && rng.one_in(3) {
+                packet(rng, depth + 1)
+            } else {
+                rng.range(0..=10).to_string()
+            }
+        })
+        .join(",");
+    format!("[{items}]")
+}
+
+fn packet_pairs(rng: &mut Rng, n_pairs: usize) -> String {
+    (0..n_pairs)
+        .map(|_| format!("{}\n{}\n", packet(rng, 0), packet(rng, 0)))
+        .join("\n")
+}
+
+#[test]
+fn test_generated_inputs() {
+    use crate::days::{registry, solution::Part};
+
+    for day in DAYS {
+        let input = generate(day, 30, 7).unwrap();
+        assert_eq!(generate(day, 30, 7).unwrap(), input, "Day {day}");
+        assert_ne!(generate(day, 30, 8).unwrap(), input, "Day {day}");
+
+        let puzzle = registry::get(day).unwrap();
+        let solver = puzzle
+            .parse(&input)
+            .unwrap_or_else(|err| panic!("{err}\n{input}"));
+        for part in Part::ALL {
+            assert!(solver.solve(part).is_ok(), "Day {day} Part {part}\n{input}");
+        }
+    }
+    assert_eq!(generate(14, 30, 7), None);
+}
@@ -0,0 +1,138 @@
+use std::{
+    env,
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+
+use crate::days::input;
+
+/// Environment variable with the directory of puzzle inputs. Takes precedence over the config file.
+pub const INPUTS_DIR_VAR: &str = "AOC_INPUTS_DIR";
+
+/// Optional config file with the directory of puzzle inputs.
+/// ```toml
+/// [inputs]
+/// dir = "../inputs/2022"
+/// ```
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// Directory of puzzle inputs used when neither the environment variable nor the config file set one.
+pub const DEFAULT_INPUTS_DIR: &str = "data";
+
+/// Directory of puzzle inputs following the `day_N_1.txt` naming convention.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputStore {
+    dir: PathBuf,
+}
+
+impl InputStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        InputStore { dir: dir.into() }
+    }
+
+    /// Find the inputs directory from `AOC_INPUTS_DIR`, then `aoc.toml`, then the default `data`.
+    pub fn from_env() -> Result<Self, Box<dyn Error>> {
+        if let Some(dir) = env::var_os(INPUTS_DIR_VAR).filter(|dir| !dir.is_empty()) {
+            return Ok(InputStore::new(dir));
+        }
+        let config = match fs::read_to_string(CONFIG_FILE) {
+            Ok(config) => config,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(InputStore::new(DEFAULT_INPUTS_DIR))
+            }
+            Err(err) => return Err(format!("Can't read {CONFIG_FILE}. {err}").into()),
+        };
+        let table = config.parse::<toml::Table>()?;
+        match table.get("inputs").and_then(|inputs| inputs.get("dir")) {
+            Some(toml::Value::String(dir)) => Ok(InputStore::new(dir)),
+            Some(_) => {
+                Err(format!("Expected 'inputs.dir' in {CONFIG_FILE} to be a string.").into())
+            }
+            None => Ok(InputStore::new(DEFAULT_INPUTS_DIR)),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where a day's input is stored, whether or not it exists.
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day_{day}_1.txt"))
+    }
+
+    /// Path to a day's input. Errors with a hint to import the input if it's missing.
+    pub fn resolve(&self, day: u8) -> Result<PathBuf, io::Error> {
+        let path = self.path(day);
+        if path.is_file() {
+            Ok(path)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "No input for day {day} at {}. Import it with `aoc_2022 import --day {day} <file>`.",
+                    path.display()
+                ),
+            ))
+        }
+    }
+
+    /// Copy a downloaded input into the store as the day's input and return its path.
+    /// * The input is normalized with `normalize`.
+    /// * An existing input is only replaced if `overwrite` is set.
+    pub fn import(&self, day: u8, source: &Path, overwrite: bool) -> Result<PathBuf, io::Error> {
+        let contents = fs::read_to_string(source)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", source.display())))?;
+        let path = self.path(day);
+        if path.exists() && !overwrite {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "Day {day} already has an input at {}. Use --force to replace it.",
+                    path.display()
+                ),
+            ));
+        }
+        fs::create_dir_all(&self.dir)?;
+        fs::write(&path, normalize(&contents))?;
+        Ok(path)
+    }
+}
+
+/// Normalize a downloaded input: drop a byte order mark, use LF line endings, remove trailing whitespace
+/// from each line and end with a single newline.
+pub fn normalize(contents: &str) -> String {
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+    let mut lines = input::lines(contents).map(|(_, line)| line).collect_vec();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+#[test]
+fn test_import_input() {
+    let dir = env::temp_dir().join(format!("aoc_2022_inputs_{}", std::process::id()));
+    let store = InputStore::new(&dir);
+    let source = env::temp_dir().join(format!("aoc_2022_download_{}.txt", std::process::id()));
+    fs::write(&source, "\u{feff}    [D]    \r\n1 2 \r\n\r\n\r\n").unwrap();
+
+    assert!(store
+        .resolve(5)
+        .unwrap_err()
+        .to_string()
+        .contains("aoc_2022 import --day 5"));
+    let path = store.import(5, &source, false).unwrap();
+    assert_eq!(store.resolve(5).unwrap(), path);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "    [D]\n1 2\n");
+    assert_eq!(
+        store.import(5, &source, false).unwrap_err().kind(),
+        io::ErrorKind::AlreadyExists
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+    fs::remove_file(source).unwrap();
+}
@@ -0,0 +1,37 @@
+//! Advent of Code 2022 solutions.
+//!
+//! Each day lives in [`days`] and implements [`Solution`]. The days' model types are
+//! re-exported here so they can be used without knowing which day they came from.
+//! * [`generate`] generates random, well-formed inputs of any size for stress testing.
+//! * [`inputs`] finds each day's input in a configurable directory and imports downloaded inputs.
+//! * [`runner`] times a day's parse and solve phases and formats the results as a table or JSON lines.
+//! * [`verify`] checks answers against a TOML file of expected answers.
+//! * [`watch`] re-runs a day whenever its solver or input changes.
+
+pub mod days;
+pub mod generate;
+pub mod inputs;
+pub mod runner;
+pub mod verify;
+pub mod watch;
+
+pub use days::{
+    common::Grid,
+    day1::{CalorieReport, Day1, ElfCalories, Inventory, Rebalance, TopElves},
+    day10::{Crt, Day10, SimpleCPU},
+    day11::{Barrel, Day11, Monkey},
+    day12::Day12,
+    day13::{Day13, Packet, PacketPair},
+    day2::{Day2, Game, GuideDecoding, Interpretation},
+    day3::Day3,
+    day4::Day4,
+    day5::{CrateMove, Day5, Supplies},
+    day6::Day6,
+    day7::{Day7, Dir, File, FileSystem},
+    day8::Day8,
+    day9::{Day9, Rope},
+    error::AocError,
+    point::{Direction, Point},
+    registry::PUZZLES,
+    solution::{Answer, Part, Puzzle, Solution, Solver},
+};
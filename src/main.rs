@@ -1,43 +1,276 @@
-use itertools::Itertools;
-use std::env;
-
-mod days;
-
-// use crate::days::day1;
-// use crate::days::day2;
-// use crate::days::day3;
-// use crate::days::day4;
-// use crate::days::day5;
-// use crate::days::day6;
-// use crate::days::day7;
-// use crate::days::day8;
-// use crate::days::day9;
-// use crate::days::day10;
-// use crate::days::day11;
-// use crate::days::day12;
-use crate::days::day13;
+use std::{io, path::PathBuf, process, time::Duration};
 
-fn main() {
-    let args = env::args().collect_vec();
-
-    let fname = args.get(1).expect("No filename provided.");
-    // let res = day1::max_calories(fname, 3);
-    // let res = day2::rps_2(fname);
-    // let res = day3::elf_groups(fname);
-    // let res = day4::camp_cleanup_overlap(fname);
-    // let res = day5::crate_mover_9001(fname);
-    // let res = day6::read_comm_message(fname);
-    // let res = day7::free_space_file_system(fname);
-    // let res = day8::tree_scenic_scores(fname);
-    // let res = day9::rope_movement(fname);
-    // let res = day10::race_the_beam(fname);
-    // let res = day11::monkey_business(fname);
-    // let res = day12::hill_climb_any_start(&fname);
-    let res = day13::distress_signal(&fname);
-
-    if let Ok(out) = res {
-        println!("{:?}", out)
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use tracing_subscriber::EnvFilter;
+
+use aoc_2022::{
+    days::registry,
+    generate,
+    inputs::InputStore,
+    runner::{self, DayRun},
+    verify::{self, ExpectedAnswers, Verdict, ANSWERS_FILE},
+    watch::Watch,
+    Answer, Part, PUZZLES,
+};
+
+/// Advent of Code 2022 solutions.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Log solver progress to stderr. `-v` for debug and `-vv` for trace.
+    /// `RUST_LOG` takes precedence so days can be picked by target, e.g. `RUST_LOG=aoc_2022::days::day9=trace`.
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run the solver for a day's part(s) against an input file.
+    Run {
+        /// Day of the puzzle.
+        #[arg(
+            short,
+            long,
+            required_unless_present = "all",
+            value_parser = clap::value_parser!(u8).range(1..=PUZZLES.len() as i64)
+        )]
+        day: Option<u8>,
+        /// Part of the puzzle. Runs both parts if omitted.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Run every day against its default input and print a table of answers and timings.
+        #[arg(long, conflicts_with_all = ["day", "input"])]
+        all: bool,
+        /// Input file, or `-` to read stdin. Defaults to the day's input in the inputs directory.
+        input: Option<String>,
+        /// How answers are printed.
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Check every day's answers, or a single day's, against the expected answers.
+    Verify {
+        /// Day of the puzzle. Verifies every day if omitted.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=PUZZLES.len() as i64))]
+        day: Option<u8>,
+        /// TOML file of expected answers.
+        #[arg(long, default_value = ANSWERS_FILE)]
+        answers: String,
+    },
+    /// Run a day's part(s) and run them again whenever the day's solver or input changes.
+    Watch {
+        /// Day of the puzzle.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=PUZZLES.len() as i64))]
+        day: u8,
+        /// Part of the puzzle. Runs both parts if omitted.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Input file. Defaults to the day's input in the inputs directory.
+        input: Option<PathBuf>,
+        /// Milliseconds between checks for changes.
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+    },
+    /// Copy a downloaded input into the inputs directory as a day's input.
+    ///
+    /// The inputs directory is set by `AOC_INPUTS_DIR`, then `[inputs] dir` in `aoc.toml`, and defaults to `data`.
+    Import {
+        /// Day of the puzzle.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Downloaded input file.
+        file: PathBuf,
+        /// Replace the day's existing input.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print a random, well-formed input for a day, e.g. to stress test a solver with a much larger input.
+    Generate {
+        /// Day of the puzzle.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=PUZZLES.len() as i64))]
+        day: u8,
+        /// How large the input is. What it counts depends on the day, like elves for day 1 or monkeys for day 11.
+        #[arg(short, long)]
+        size: usize,
+        /// Seed of the random input. The same seed always generates the same input.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    /// Each part's answer, or a table of answers and timings with `--all`.
+    Text,
+    /// A JSON object per day and part with its answer and timings.
+    Json,
+}
+
+fn print_answer(day: u8, part: Part, answer: &Answer) {
+    let answer = answer.to_string();
+    // Put multi-line answers, like a rendered screen, below the label.
+    if answer.contains('\n') {
+        println!("Day {day} Part {part}:\n{answer}")
     } else {
-        panic!("{}", res.unwrap_err())
+        println!("Day {day} Part {part}: {answer}")
+    }
+}
+
+/// Print the answer or error of each part. A failed part doesn't hide the other.
+fn print_run(run: &DayRun) {
+    match &run.parts {
+        Ok(parts) => {
+            for part_run in parts.iter() {
+                match &part_run.answer {
+                    Ok(answer) => print_answer(run.day, part_run.part, answer),
+                    Err(err) => eprintln!("Day {} Part {} failed: {err}", run.day, part_run.part),
+                }
+            }
+        }
+        Err(err) => eprintln!("Day {} failed: {err}", run.day),
+    }
+}
+
+/// Log to stderr so answers on stdout can be piped.
+fn init_logging(verbose: u8) {
+    let level = match verbose {
+        0 => "warn",
+        1 => "debug",
+        _ => "trace",
+    };
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .init();
+}
+
+fn main() {
+    let cli = Cli::parse();
+    init_logging(cli.verbose);
+
+    let store = match InputStore::from_env() {
+        Ok(store) => store,
+        Err(err) => {
+            eprintln!("Can't find the inputs directory. {err}");
+            process::exit(1)
+        }
+    };
+
+    match cli.command {
+        Command::Run {
+            day,
+            part,
+            all,
+            input,
+            format,
+        } => {
+            let parts = match part.map(Part::try_from).transpose() {
+                Ok(Some(part)) => vec![part],
+                Ok(None) => Part::ALL.to_vec(),
+                Err(err) => {
+                    eprintln!("{err}");
+                    process::exit(1)
+                }
+            };
+
+            let runs = if all {
+                PUZZLES
+                    .iter()
+                    .map(|puzzle| runner::run_stored(puzzle, &parts, &store))
+                    .collect::<Vec<DayRun>>()
+            } else {
+                // Day is required unless running all days.
+                let day = day.unwrap_or_default();
+                let Some(puzzle) = registry::get(day) else {
+                    eprintln!("No solution for day {day}.");
+                    process::exit(1)
+                };
+                let run = match input.as_deref() {
+                    None => runner::run_stored(&puzzle, &parts, &store),
+                    Some("-") => runner::run_reader(&puzzle, &parts, io::stdin().lock()),
+                    Some(fname) => runner::run_file(&puzzle, &parts, fname),
+                };
+                vec![run]
+            };
+
+            match format {
+                Format::Text if all => println!("{}", runner::format_table(&runs)),
+                Format::Text => runs.iter().for_each(print_run),
+                Format::Json => println!("{}", runner::format_json_lines(&runs)),
+            }
+
+            if runs.iter().any(|run| run.failed()) {
+                process::exit(1)
+            }
+        }
+        Command::Verify { day, answers } => {
+            let expected = match ExpectedAnswers::from_file(&answers) {
+                Ok(expected) => expected,
+                Err(err) => {
+                    eprintln!("Can't load expected answers from {answers}. {err}");
+                    process::exit(1)
+                }
+            };
+
+            let (mut n_passed, mut n_failed, mut n_missing) = (0, 0, 0);
+            for puzzle in PUZZLES
+                .iter()
+                .filter(|puzzle| day.is_none_or(|day| puzzle.day == day))
+            {
+                let run = runner::run_stored(puzzle, &Part::ALL, &store);
+                for (part, verdict) in verify::check(&run, &Part::ALL, &expected) {
+                    println!("Day {} Part {part}: {verdict}", puzzle.day);
+                    match verdict {
+                        Verdict::Pass => n_passed += 1,
+                        Verdict::Missing => n_missing += 1,
+                        _ => n_failed += 1,
+                    }
+                }
+            }
+            println!("{n_passed} passed, {n_failed} failed, {n_missing} missing.");
+
+            if n_failed > 0 {
+                process::exit(1)
+            }
+        }
+        Command::Watch {
+            day,
+            part,
+            input,
+            interval,
+        } => {
+            let Some(puzzle) = registry::get(day) else {
+                eprintln!("No solution for day {day}.");
+                process::exit(1)
+            };
+            let parts = match part.map(Part::try_from).transpose() {
+                Ok(Some(part)) => vec![part],
+                Ok(None) => Part::ALL.to_vec(),
+                Err(err) => {
+                    eprintln!("{err}");
+                    process::exit(1)
+                }
+            };
+            let input = input.unwrap_or_else(|| store.path(day));
+            let mut watch = Watch::new(puzzle, parts, input);
+            watch.interval = Duration::from_millis(interval);
+            watch.run()
+        }
+        Command::Import { day, file, force } => match store.import(day, &file, force) {
+            Ok(path) => println!("Imported day {day} input to {}.", path.display()),
+            Err(err) => {
+                eprintln!("Can't import {}. {err}", file.display());
+                process::exit(1)
+            }
+        },
+        Command::Generate { day, size, seed } => match generate::generate(day, size, seed) {
+            Some(input) => print!("{input}"),
+            None => {
+                eprintln!("No input generator for day {day}.");
+                process::exit(1)
+            }
+        },
     }
 }
@@ -0,0 +1,284 @@
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+    days::{
+        common::read_input,
+        error::AocError,
+        solution::{Answer, Part, Puzzle},
+    },
+    inputs::InputStore,
+};
+
+/// Answer to a part and how long it took to solve.
+pub struct PartRun {
+    pub part: Part,
+    pub answer: Result<Answer, AocError>,
+    pub solve_time: Duration,
+}
+
+/// Results for a day. Input is parsed once and shared by every part.
+pub struct DayRun {
+    pub day: u8,
+    pub parse_time: Duration,
+    pub parts: Result<Vec<PartRun>, AocError>,
+}
+
+impl DayRun {
+    /// Run of a day whose input couldn't be read.
+    fn unread(day: u8, err: AocError) -> Self {
+        DayRun {
+            day,
+            parse_time: Duration::ZERO,
+            parts: Err(err),
+        }
+    }
+
+    /// Whether the input or any part failed.
+    pub fn failed(&self) -> bool {
+        match &self.parts {
+            Ok(parts) => parts.iter().any(|part| part.answer.is_err()),
+            Err(_) => true,
+        }
+    }
+}
+
+/// Parse a day's input and solve the given parts, timing each phase.
+pub fn run_puzzle(puzzle: &Puzzle, parts: &[Part], input: &str) -> DayRun {
+    let start = Instant::now();
+    let parsed = puzzle.parse(input);
+    let parse_time = start.elapsed();
+
+    let parts = parsed.map(|solver| {
+        parts
+            .iter()
+            .map(|part| {
+                let start = Instant::now();
+                let answer = solver.solve(*part);
+                PartRun {
+                    part: *part,
+                    answer,
+                    solve_time: start.elapsed(),
+                }
+            })
+            .collect_vec()
+    });
+
+    DayRun {
+        day: puzzle.day,
+        parse_time,
+        parts,
+    }
+}
+
+/// Same as `run_puzzle` but reads the input from a reader. Reading isn't included in the parse time.
+pub fn run_reader(puzzle: &Puzzle, parts: &[Part], reader: impl Read) -> DayRun {
+    match read_input(reader) {
+        Ok(contents) => run_puzzle(puzzle, parts, &contents),
+        Err(err) => DayRun::unread(puzzle.day, err.into()),
+    }
+}
+
+/// Same as `run_puzzle` but reads the input from a file.
+pub fn run_file(puzzle: &Puzzle, parts: &[Part], fname: impl AsRef<Path>) -> DayRun {
+    let fname = fname.as_ref();
+    match File::open(fname) {
+        Ok(file) => run_reader(puzzle, parts, file),
+        Err(err) => DayRun::unread(
+            puzzle.day,
+            io::Error::new(err.kind(), format!("{}: {err}", fname.display())).into(),
+        ),
+    }
+}
+
+/// Same as `run_puzzle` but reads the day's input from an input store.
+pub fn run_stored(puzzle: &Puzzle, parts: &[Part], store: &InputStore) -> DayRun {
+    match store.resolve(puzzle.day) {
+        Ok(path) => run_file(puzzle, parts, path),
+        Err(err) => DayRun::unread(puzzle.day, err.into()),
+    }
+}
+
+/// Format a table of answers with the parse and solve time of each part.
+/// * Multi-line answers, like a rendered screen, are placed below the table.
+pub fn format_table(runs: &[DayRun]) -> String {
+    let header = ["Day", "Part", "Answer", "Parse", "Solve"].map(String::from);
+    let mut rows: Vec<[String; 5]> = vec![];
+    let mut multi_line_answers: Vec<(u8, Part, String)> = vec![];
+    let mut total_time = Duration::ZERO;
+
+    for run in runs.iter() {
+        total_time += run.parse_time;
+        match &run.parts {
+            Ok(parts) => {
+                for part_run in parts.iter() {
+                    total_time += part_run.solve_time;
+                    let answer = match &part_run.answer {
+                        Ok(answer) => {
+                            let answer = answer.to_string();
+                            if answer.contains('\n') {
+                                multi_line_answers.push((run.day, part_run.part, answer));
+                                "(see below)".to_string()
+                            } else {
+                                answer
+                            }
+                        }
+                        Err(err) => format!("error: {err}"),
+                    };
+                    rows.push([
+                        run.day.to_string(),
+                        part_run.part.to_string(),
+                        answer,
+                        format!("{:.2?}", run.parse_time),
+                        format!("{:.2?}", part_run.solve_time),
+                    ])
+                }
+            }
+            Err(err) => rows.push([
+                run.day.to_string(),
+                "-".to_string(),
+                format!("error: {err}"),
+                format!("{:.2?}", run.parse_time),
+                "-".to_string(),
+            ]),
+        }
+    }
+
+    // Size each column to its widest cell.
+    let widths = (0..header.len())
+        .map(|col| {
+            rows.iter()
+                .chain([&header])
+                .map(|row| row[col].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect_vec();
+    let format_row = |row: &[String; 5]| {
+        row.iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(col, (cell, width))| {
+                // Answers are left-aligned. Numbers and times are right-aligned.
+                if col == 2 {
+                    format!("{cell:<width$}")
+                } else {
+                    format!("{cell:>width$}")
+                }
+            })
+            .join(" | ")
+    };
+
+    let mut lines = vec![
+        format_row(&header),
+        widths.iter().map(|width| "-".repeat(*width)).join("-|-"),
+    ];
+    lines.extend(rows.iter().map(format_row));
+    lines.push(format!("Total: {total_time:.2?}"));
+
+    for (day, part, answer) in multi_line_answers {
+        lines.push(format!("\nDay {day} Part {part}:\n{answer}"));
+    }
+    lines.join("\n")
+}
+
+/// A part's result, or a day's parse failure, as one line of JSON output.
+#[derive(Debug, Serialize)]
+struct PartRecord<'a> {
+    day: u8,
+    /// Missing when the day's input failed to parse.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    answer: Option<&'a Answer>,
+    /// Rows of image-like answers, like the rendered CRT screen.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rows: Option<&'a [String]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    parse_ns: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    solve_ns: Option<u128>,
+}
+
+/// Format runs as JSON lines with an object for each part.
+/// * Answers are numbers or strings. Image-like answers also include their `rows`.
+/// * Failed parts, and days whose input failed to parse, have an `error` instead of an `answer`.
+pub fn format_json_lines(runs: &[DayRun]) -> String {
+    let mut records = vec![];
+    for run in runs.iter() {
+        let record = PartRecord {
+            day: run.day,
+            part: None,
+            answer: None,
+            rows: None,
+            error: None,
+            parse_ns: run.parse_time.as_nanos(),
+            solve_ns: None,
+        };
+        match &run.parts {
+            Ok(parts) => records.extend(parts.iter().map(|part_run| {
+                let (answer, error) = match &part_run.answer {
+                    Ok(answer) => (Some(answer), None),
+                    Err(err) => (None, Some(err.to_string())),
+                };
+                PartRecord {
+                    part: Some(part_run.part as u8),
+                    answer,
+                    rows: match answer {
+                        Some(Answer::Image(rows)) => Some(rows.as_slice()),
+                        _ => None,
+                    },
+                    error,
+                    solve_ns: Some(part_run.solve_time.as_nanos()),
+                    ..record
+                }
+            })),
+            Err(err) => records.push(PartRecord {
+                error: Some(err.to_string()),
+                ..record
+            }),
+        }
+    }
+    records
+        .iter()
+        .map(|record| {
+            serde_json::to_string(record).expect("Records only hold strings and numbers.")
+        })
+        .join("\n")
+}
+
+#[test]
+fn test_json_lines() {
+    let runs = [
+        DayRun {
+            day: 10,
+            parse_time: Duration::from_nanos(5),
+            parts: Ok(vec![PartRun {
+                part: Part::Two,
+                answer: Ok(Answer::Image(vec!["#.".to_string(), ".#".to_string()])),
+                solve_time: Duration::from_nanos(7),
+            }]),
+        },
+        DayRun {
+            day: 4,
+            parse_time: Duration::ZERO,
+            parts: Err(AocError::unsolvable(4, "No assignments.")),
+        },
+    ];
+    assert_eq!(
+        format_json_lines(&runs),
+        [
+            r##"{"day":10,"part":2,"answer":"#.\n.#","rows":["#.",".#"],"parse_ns":5,"solve_ns":7}"##,
+            r#"{"day":4,"error":"Day 4: No assignments.","parse_ns":0}"#,
+        ]
+        .join("\n")
+    );
+}
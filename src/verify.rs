@@ -0,0 +1,247 @@
+use std::{collections::HashMap, error::Error, fmt, fs, str::FromStr};
+
+use crate::{
+    days::{
+        error::AocError,
+        solution::{Answer, Part},
+    },
+    runner::DayRun,
+};
+
+/// Default location of the expected answers.
+pub const ANSWERS_FILE: &str = "data/answers.toml";
+
+/// Location of the expected answers for the example inputs.
+pub const EXAMPLE_ANSWERS_FILE: &str = "data/test_answers.toml";
+
+/// Expected answers keyed by day and part.
+#[derive(Debug, Default)]
+pub struct ExpectedAnswers {
+    answers: HashMap<(u8, Part), Answer>,
+}
+
+impl ExpectedAnswers {
+    pub fn from_file(fname: &str) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(fname)?;
+        ExpectedAnswers::from_str(&contents)
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+}
+
+/// Parse the numeric suffix of a key like `day12` or `part1`.
+fn parse_key(key: &str, prefix: &str) -> Result<u8, String> {
+    key.strip_prefix(prefix)
+        .and_then(|num| num.parse::<u8>().ok())
+        .ok_or(format!("Invalid key '{key}'. Expected '{prefix}N'."))
+}
+
+/// Parse a table with a `partN` key for each part's answer.
+fn parse_parts(key: &str, parts: &toml::Value) -> Result<Vec<(Part, Answer)>, Box<dyn Error>> {
+    let parts = parts
+        .as_table()
+        .ok_or(format!("Expected a table of parts for '{key}'."))?;
+
+    let mut answers = vec![];
+    for (part_key, value) in parts.iter() {
+        let part = Part::try_from(parse_key(part_key, "part")?)?;
+        let answer = match value {
+            toml::Value::Integer(num) => Answer::Number((*num).into()),
+            toml::Value::String(text) => Answer::Text(text.clone()),
+            _ => {
+                return Err(
+                    format!("Answer for {key}.{part_key} must be an integer or a string.").into(),
+                )
+            }
+        };
+        answers.push((part, answer));
+    }
+    Ok(answers)
+}
+
+impl FromStr for ExpectedAnswers {
+    type Err = Box<dyn Error>;
+
+    /// Parse TOML with a `[dayN]` table for each day and a `partN` key for each part.
+    /// ```toml
+    /// [day5]
+    /// part1 = "CMZ"
+    /// part2 = "MCD"
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table = s.parse::<toml::Table>()?;
+        let mut answers = HashMap::new();
+
+        for (day_key, parts) in table.iter() {
+            let day = parse_key(day_key, "day")?;
+            for (part, answer) in parse_parts(day_key, parts)? {
+                answers.insert((day, part), answer);
+            }
+        }
+        Ok(ExpectedAnswers { answers })
+    }
+}
+
+/// Expected answers for example inputs keyed by the input's file stem, like `test_day_9_2`, and part.
+#[derive(Debug, Default)]
+pub struct ExampleAnswers {
+    answers: HashMap<(String, Part), Answer>,
+}
+
+impl ExampleAnswers {
+    pub fn from_file(fname: &str) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(fname)?;
+        ExampleAnswers::from_str(&contents)
+    }
+
+    pub fn get(&self, example: &str, part: Part) -> Option<&Answer> {
+        self.answers.get(&(example.to_string(), part))
+    }
+}
+
+impl FromStr for ExampleAnswers {
+    type Err = Box<dyn Error>;
+
+    /// Parse TOML with a table for each example and a `partN` key for each part.
+    /// ```toml
+    /// [test_day_9_2]
+    /// part1 = 88
+    /// part2 = 36
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table = s.parse::<toml::Table>()?;
+        let mut answers = HashMap::new();
+
+        for (example, parts) in table.iter() {
+            for (part, answer) in parse_parts(example, parts)? {
+                answers.insert((example.clone(), part), answer);
+            }
+        }
+        Ok(ExampleAnswers { answers })
+    }
+}
+
+/// Outcome of checking a part's answer against its expected answer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer, actual: Answer },
+    Missing,
+    Error(String),
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {expected}, got {actual})")
+            }
+            Verdict::Missing => write!(f, "missing expected answer"),
+            Verdict::Error(err) => write!(f, "ERROR ({err})"),
+        }
+    }
+}
+
+/// Text answers are compared without surrounding whitespace so multi-line answers can be written naturally in TOML.
+/// * Images are compared to text answers by their rendered rows.
+fn answers_match(expected: &Answer, actual: &Answer) -> bool {
+    match (expected, actual) {
+        (Answer::Text(expected), Answer::Text(_) | Answer::Image(_)) => {
+            expected.trim() == actual.to_string().trim()
+        }
+        _ => expected == actual,
+    }
+}
+
+/// Check a part's answer, or the error from solving it, against its expected answer.
+pub fn verdict(expected: Option<&Answer>, actual: &Result<Answer, AocError>) -> Verdict {
+    match (actual, expected) {
+        (Err(err), _) => Verdict::Error(err.to_string()),
+        (Ok(_), None) => Verdict::Missing,
+        (Ok(actual), Some(expected)) => {
+            if answers_match(expected, actual) {
+                Verdict::Pass
+            } else {
+                Verdict::Fail {
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                }
+            }
+        }
+    }
+}
+
+/// Check each requested part of a run against the expected answers.
+pub fn check(run: &DayRun, parts: &[Part], expected: &ExpectedAnswers) -> Vec<(Part, Verdict)> {
+    match &run.parts {
+        Ok(part_runs) => part_runs
+            .iter()
+            .map(|part_run| {
+                (
+                    part_run.part,
+                    verdict(expected.get(run.day, part_run.part), &part_run.answer),
+                )
+            })
+            .collect(),
+        Err(err) => parts
+            .iter()
+            .map(|part| (*part, Verdict::Error(err.to_string())))
+            .collect(),
+    }
+}
+
+#[test]
+fn test_check_answers() {
+    use crate::runner::PartRun;
+    use std::time::Duration;
+
+    let expected =
+        ExpectedAnswers::from_str("[day5]\npart1 = \"CMZ\"\n[day6]\npart1 = 7\npart2 = 19\n")
+            .unwrap();
+    let part_run = |part: Part, answer: Answer| PartRun {
+        part,
+        answer: Ok(answer),
+        solve_time: Duration::ZERO,
+    };
+    let run = |day: u8, parts: Vec<PartRun>| DayRun {
+        day,
+        parse_time: Duration::ZERO,
+        parts: Ok(parts),
+    };
+
+    let day_5 = run(
+        5,
+        vec![
+            part_run(Part::One, Answer::Text("CMZ".to_string())),
+            part_run(Part::Two, Answer::Text("MCD".to_string())),
+        ],
+    );
+    let day_6 = run(
+        6,
+        vec![
+            part_run(Part::One, Answer::Number(7)),
+            part_run(Part::Two, Answer::Number(23)),
+        ],
+    );
+
+    assert_eq!(
+        check(&day_5, &Part::ALL, &expected),
+        [(Part::One, Verdict::Pass), (Part::Two, Verdict::Missing)]
+    );
+    assert_eq!(
+        check(&day_6, &Part::ALL, &expected),
+        [
+            (Part::One, Verdict::Pass),
+            (
+                Part::Two,
+                Verdict::Fail {
+                    expected: Answer::Number(19),
+                    actual: Answer::Number(23)
+                }
+            )
+        ]
+    );
+}
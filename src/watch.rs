@@ -0,0 +1,248 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use itertools::Itertools;
+
+use crate::{
+    days::solution::{Part, Puzzle},
+    runner::{self, DayRun},
+};
+
+/// Answer, or error, of a part and how long it took to solve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub part: Part,
+    pub answer: Result<String, String>,
+    pub solve_time: Duration,
+}
+
+/// Outcome of each requested part of a run. Every part has the day's error if its input failed.
+pub fn outcomes(run: &DayRun, parts: &[Part]) -> Vec<Outcome> {
+    match &run.parts {
+        Ok(part_runs) => part_runs
+            .iter()
+            .map(|part_run| Outcome {
+                part: part_run.part,
+                answer: part_run
+                    .answer
+                    .as_ref()
+                    .map(|answer| answer.to_string())
+                    .map_err(|err| err.to_string()),
+                solve_time: part_run.solve_time,
+            })
+            .collect(),
+        Err(err) => parts
+            .iter()
+            .map(|part| Outcome {
+                part: *part,
+                answer: Err(err.to_string()),
+                solve_time: Duration::ZERO,
+            })
+            .collect(),
+    }
+}
+
+/// Outcomes of the requested parts from the JSON lines printed by `run --format json`.
+pub fn parse_json_outcomes(json_lines: &str, parts: &[Part]) -> Result<Vec<Outcome>, String> {
+    let mut outcomes = vec![];
+    for line in json_lines.lines().filter(|line| !line.trim().is_empty()) {
+        let record = serde_json::from_str::<serde_json::Value>(line)
+            .map_err(|err| format!("Unexpected output '{line}'. {err}"))?;
+        let answer = match (&record["answer"], &record["error"]) {
+            (serde_json::Value::Number(num), _) => Ok(num.to_string()),
+            (serde_json::Value::String(text), _) => Ok(text.clone()),
+            (_, serde_json::Value::String(err)) => Err(err.clone()),
+            _ => return Err(format!("No answer or error in '{line}'.")),
+        };
+        let solve_time = Duration::from_nanos(record["solve_ns"].as_u64().unwrap_or(0));
+
+        match record["part"].as_u64() {
+            Some(part) => outcomes.push(Outcome {
+                part: Part::try_from(part as u8)?,
+                answer,
+                solve_time,
+            }),
+            // The day's input failed, so every part failed.
+            None => outcomes.extend(parts.iter().map(|part| Outcome {
+                part: *part,
+                answer: answer.clone(),
+                solve_time,
+            })),
+        }
+    }
+    Ok(outcomes)
+}
+
+/// Describe each part's outcome and how it changed from the previous run.
+pub fn format_changes(day: u8, previous: &[Outcome], current: &[Outcome]) -> String {
+    current
+        .iter()
+        .map(|outcome| {
+            let label = format!("Day {day} Part {}", outcome.part);
+            let before = previous
+                .iter()
+                .find(|prev| prev.part == outcome.part)
+                .map(|prev| &prev.answer);
+            let change = match before {
+                None => "",
+                Some(before) if *before == outcome.answer => " (unchanged)",
+                Some(_) => " (changed)",
+            };
+            match (&outcome.answer, before) {
+                (Err(err), _) => format!("{label} failed{change}: {err}"),
+                (Ok(answer), _) if answer.contains('\n') => {
+                    format!("{label}{change} in {:.2?}:\n{answer}", outcome.solve_time)
+                }
+                (Ok(answer), Some(Ok(before))) if before != answer => {
+                    format!(
+                        "{label}: {before} -> {answer} in {:.2?}",
+                        outcome.solve_time
+                    )
+                }
+                (Ok(answer), _) => {
+                    format!("{label}: {answer}{change} in {:.2?}", outcome.solve_time)
+                }
+            }
+        })
+        .join("\n")
+}
+
+/// Files of a day to watch and how to run it.
+#[derive(Debug, Clone)]
+pub struct Watch {
+    pub puzzle: Puzzle,
+    pub parts: Vec<Part>,
+    pub input: PathBuf,
+    pub source: PathBuf,
+    /// How often files are checked for changes.
+    pub interval: Duration,
+}
+
+impl Watch {
+    /// Watch a day's solver source in this crate and its input.
+    pub fn new(puzzle: Puzzle, parts: Vec<Part>, input: PathBuf) -> Self {
+        let source = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/days")
+            .join(format!("day{}.rs", puzzle.day));
+        Watch {
+            puzzle,
+            parts,
+            input,
+            source,
+            interval: Duration::from_millis(500),
+        }
+    }
+
+    /// Run the day now and again whenever its source or input changes. Never returns.
+    /// * Input changes are run with the solvers built into this binary.
+    /// * Once the source changes, the built-in solver is stale, so `cargo run` rebuilds and runs the day instead.
+    pub fn run(&self) -> ! {
+        let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        let mut input_modified = modified(&self.input);
+        let mut source_modified = modified(&self.source);
+        let mut source_changed = false;
+
+        println!(
+            "Watching {} and {}.",
+            self.source.display(),
+            self.input.display()
+        );
+        let mut previous = self.run_in_process();
+        println!("{}", format_changes(self.puzzle.day, &[], &previous));
+
+        loop {
+            thread::sleep(self.interval);
+            let (input_now, source_now) = (modified(&self.input), modified(&self.source));
+            if source_now != source_modified {
+                source_changed = true;
+                println!("\n{} changed.", self.source.display());
+            } else if input_now != input_modified {
+                println!("\n{} changed.", self.input.display());
+            } else {
+                continue;
+            }
+            (input_modified, source_modified) = (input_now, source_now);
+
+            let current = if source_changed {
+                self.run_with_cargo()
+            } else {
+                Ok(self.run_in_process())
+            };
+            match current {
+                Ok(current) => {
+                    println!("{}", format_changes(self.puzzle.day, &previous, &current));
+                    previous = current;
+                }
+                Err(err) => eprintln!("Can't run day {}. {err}", self.puzzle.day),
+            }
+        }
+    }
+
+    fn run_in_process(&self) -> Vec<Outcome> {
+        let run = runner::run_file(&self.puzzle, &self.parts, &self.input);
+        outcomes(&run, &self.parts)
+    }
+
+    /// Rebuild the crate with the same profile as this binary and run the day. Cargo's output is shown as is.
+    fn run_with_cargo(&self) -> Result<Vec<Outcome>, String> {
+        let mut cargo = Command::new(env!("CARGO"));
+        cargo
+            .arg("run")
+            .arg("--quiet")
+            .arg("--manifest-path")
+            .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"));
+        if !cfg!(debug_assertions) {
+            cargo.arg("--release");
+        }
+        cargo
+            .args(["--", "run", "--format", "json", "--day"])
+            .arg(self.puzzle.day.to_string())
+            .arg(&self.input);
+        // Both parts run when no part is given.
+        if let [part] = self.parts[..] {
+            cargo.args(["--part", &part.to_string()]);
+        }
+
+        let output = cargo
+            .stderr(Stdio::inherit())
+            .output()
+            .map_err(|err| format!("Can't run cargo. {err}"))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.trim().is_empty() {
+            return Err(format!("cargo exited with {}.", output.status));
+        }
+        parse_json_outcomes(&stdout, &self.parts)
+    }
+}
+
+#[test]
+fn test_watch_changes() {
+    let json = concat!(
+        r#"{"day":11,"part":1,"answer":10605,"parse_ns":5,"solve_ns":7}"#,
+        "\n",
+        r#"{"day":11,"part":2,"error":"Day 11: Need at least two monkeys.","parse_ns":5,"solve_ns":2}"#,
+    );
+    let previous = [
+        Outcome {
+            part: Part::One,
+            answer: Ok("10605".to_string()),
+            solve_time: Duration::ZERO,
+        },
+        Outcome {
+            part: Part::Two,
+            answer: Ok("2713310158".to_string()),
+            solve_time: Duration::ZERO,
+        },
+    ];
+    let current = parse_json_outcomes(json, &Part::ALL).unwrap();
+    assert_eq!(
+        format_changes(11, &previous, &current),
+        "Day 11 Part 1: 10605 (unchanged) in 7.00ns\n\
+         Day 11 Part 2 failed (changed): Day 11: Need at least two monkeys."
+    );
+}
@@ -0,0 +1,414 @@
+//! Differential tests of the solvers against slow but obviously correct reference solvers.
+//! Each reference parses the input on its own and follows the puzzle's rules literally, so it can't share a
+//! bug with the solver it checks. Both solve the same generated inputs and must give the same answers.
+
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    iter::Peekable,
+    str::Chars,
+};
+
+use itertools::Itertools;
+
+use aoc_2022::{days::registry, generate, Part};
+
+/// Generated inputs per day.
+const N_SEEDS: u64 = 25;
+
+/// Solve generated inputs of a day with both its solver and a reference and compare their answers.
+fn check_against_reference(day: u8, size: usize, reference: fn(&str) -> (usize, usize)) {
+    let puzzle = registry::get(day).unwrap_or_else(|| panic!("No solution for day {day}."));
+    for seed in 0..N_SEEDS {
+        let input = generate::generate(day, size, seed).unwrap();
+        let solver = puzzle.parse(&input).unwrap();
+        let (part_1, part_2) = reference(&input);
+        for (part, expected) in Part::ALL.into_iter().zip([part_1, part_2]) {
+            let answer = solver.solve(part).unwrap();
+            assert_eq!(
+                answer.to_string(),
+                expected.to_string(),
+                "Day {day} Part {part} of the input generated with seed {seed}:\n{input}"
+            );
+        }
+    }
+}
+
+/// Sizes of directories by path, found by adding each file's size to every directory above it.
+fn reference_day7(input: &str) -> (usize, usize) {
+    let mut sizes: HashMap<Vec<&str>, usize> = HashMap::from([(vec![], 0)]);
+    let mut cwd: Vec<&str> = vec![];
+    for line in input.lines() {
+        match line.split_whitespace().collect_vec()[..] {
+            ["$", "cd", "/"] => cwd.clear(),
+            ["$", "cd", ".."] => {
+                cwd.pop();
+            }
+            ["$", "cd", name] => cwd.push(name),
+            ["$", "ls"] => {}
+            ["dir", name] => {
+                sizes.entry([&cwd[..], &[name]].concat()).or_insert(0);
+            }
+            [size, _] => {
+                let size = size.parse::<usize>().unwrap();
+                for depth in 0..=cwd.len() {
+                    *sizes.entry(cwd[..depth].to_vec()).or_insert(0) += size;
+                }
+            }
+            _ => panic!("Unexpected line '{line}'."),
+        }
+    }
+
+    let small = sizes.values().filter(|size| **size <= 100_000).sum();
+    // Space to free so that 30000000 of the 70000000 disk is unused.
+    let needed = (sizes[&vec![]] + 30_000_000).saturating_sub(70_000_000);
+    let smallest = sizes.values().filter(|size| **size >= needed).min();
+    (small, *smallest.unwrap())
+}
+
+/// Look from every tree in each direction with nested loops.
+fn reference_day8(input: &str) -> (usize, usize) {
+    let forest = input
+        .lines()
+        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect_vec())
+        .collect_vec();
+    let (rows, cols) = (forest.len(), forest[0].len());
+
+    let (mut visible, mut max_score) = (0, 0);
+    for row in 0..rows {
+        for col in 0..cols {
+            let height = forest[row][col];
+            let lines_of_sight = [
+                (0..row).rev().map(|r| forest[r][col]).collect_vec(),
+                (row + 1..rows).map(|r| forest[r][col]).collect_vec(),
+                (0..col).rev().map(|c| forest[row][c]).collect_vec(),
+                (col + 1..cols).map(|c| forest[row][c]).collect_vec(),
+            ];
+            if lines_of_sight
+                .iter()
+                .any(|trees| trees.iter().all(|tree| *tree < height))
+            {
+                visible += 1;
+            }
+
+            let mut score = 1;
+            for trees in lines_of_sight.iter() {
+                let mut seen = 0;
+                for tree in trees {
+                    seen += 1;
+                    if *tree >= height {
+                        break;
+                    }
+                }
+                score *= seen;
+            }
+            max_score = max_score.max(score);
+        }
+    }
+    (visible, max_score)
+}
+
+/// Where a knot moves to follow the knot ahead of it, case by case as the puzzle describes.
+fn follow(ahead: (i64, i64), knot: (i64, i64)) -> (i64, i64) {
+    let (dx, dy) = (ahead.0 - knot.0, ahead.1 - knot.1);
+    if dx.abs() <= 1 && dy.abs() <= 1 {
+        // Touching, including overlapping.
+        knot
+    } else if dy == 0 {
+        // Two steps directly left or right.
+        (knot.0 + dx / 2, knot.1)
+    } else if dx == 0 {
+        // Two steps directly up or down.
+        (knot.0, knot.1 + dy / 2)
+    } else {
+        // Not touching nor in the same row or column.
+        (knot.0 + dx.signum(), knot.1 + dy.signum())
+    }
+}
+
+fn reference_day9(input: &str) -> (usize, usize) {
+    let tail_positions = |n_knots: usize| {
+        let mut knots = vec![(0, 0); n_knots];
+        let mut visited = HashSet::from([(0, 0)]);
+        for line in input.lines() {
+            let (direction, steps) = line.split_once(' ').unwrap();
+            let (dx, dy) = match direction {
+                "U" => (0, 1),
+                "D" => (0, -1),
+                "L" => (-1, 0),
+                "R" => (1, 0),
+                _ => panic!("Unexpected direction '{direction}'."),
+            };
+            for _ in 0..steps.parse::<usize>().unwrap() {
+                knots[0] = (knots[0].0 + dx, knots[0].1 + dy);
+                for i in 1..n_knots {
+                    knots[i] = follow(knots[i - 1], knots[i]);
+                }
+                visited.insert(knots[n_knots - 1]);
+            }
+        }
+        visited.len()
+    };
+    (tail_positions(2), tail_positions(10))
+}
+
+struct ReferenceMonkey {
+    items: Vec<u64>,
+    /// Operands, `None` for the old worry level, and the operator between them.
+    operation: (Option<u64>, char, Option<u64>),
+    divisor: u64,
+    on_true: usize,
+    on_false: usize,
+}
+
+impl ReferenceMonkey {
+    /// New worry level from the operation, like `old * 3`.
+    fn operate(&self, old: u64) -> u64 {
+        let (a, operator, b) = self.operation;
+        let (a, b) = (a.unwrap_or(old), b.unwrap_or(old));
+        match operator {
+            '+' => a + b,
+            '*' => a * b,
+            operator => panic!("Unexpected operator '{operator}'."),
+        }
+    }
+
+    fn target(&self, divisible: bool) -> usize {
+        if divisible {
+            self.on_true
+        } else {
+            self.on_false
+        }
+    }
+}
+
+/// Product of the two highest numbers of inspections.
+fn monkey_business(mut inspections: Vec<usize>) -> usize {
+    inspections.sort_unstable_by(|a, b| b.cmp(a));
+    inspections[0] * inspections[1]
+}
+
+/// Without relief, each item is tracked by its worry level modulo every monkey's divisor, which is all that
+/// the divisibility tests need.
+fn reference_day11(input: &str) -> (usize, usize) {
+    let last_number = |line: &str| line.split_whitespace().last().unwrap().parse().unwrap();
+    let monkeys = input
+        .split("\n\n")
+        .map(|block| {
+            let lines = block.lines().map(str::trim).collect_vec();
+            let (_, items) = lines[1].split_once(": ").unwrap();
+            let (_, operation) = lines[2].split_once("= ").unwrap();
+            let (a, operator, b) = operation.split(' ').collect_tuple().unwrap();
+            let operand = |token: &str| token.parse::<u64>().ok();
+            ReferenceMonkey {
+                items: items
+                    .split(", ")
+                    .map(|item| item.parse().unwrap())
+                    .collect(),
+                operation: (operand(a), operator.parse().unwrap(), operand(b)),
+                divisor: last_number(lines[3]),
+                on_true: last_number(lines[4]) as usize,
+                on_false: last_number(lines[5]) as usize,
+            }
+        })
+        .collect_vec();
+
+    let mut items = monkeys
+        .iter()
+        .map(|monkey| monkey.items.clone())
+        .collect_vec();
+    let mut inspections = vec![0; monkeys.len()];
+    for _ in 0..20 {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            for worry in std::mem::take(&mut items[i]) {
+                inspections[i] += 1;
+                let worry = monkey.operate(worry) / 3;
+                items[monkey.target(worry % monkey.divisor == 0)].push(worry);
+            }
+        }
+    }
+    let part_1 = monkey_business(inspections);
+
+    let divisors = monkeys.iter().map(|monkey| monkey.divisor).collect_vec();
+    let mut items = monkeys
+        .iter()
+        .map(|monkey| {
+            monkey
+                .items
+                .iter()
+                .map(|worry| divisors.iter().map(|divisor| worry % divisor).collect_vec())
+                .collect_vec()
+        })
+        .collect_vec();
+    let mut inspections = vec![0; monkeys.len()];
+    for _ in 0..10_000 {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            for mut residues in std::mem::take(&mut items[i]) {
+                inspections[i] += 1;
+                for (residue, divisor) in residues.iter_mut().zip(divisors.iter()) {
+                    *residue = monkey.operate(*residue) % divisor;
+                }
+                items[monkey.target(residues[i] == 0)].push(residues);
+            }
+        }
+    }
+    (part_1, monkey_business(inspections))
+}
+
+/// Breadth first search from each start on its own.
+fn reference_day12(input: &str) -> (usize, usize) {
+    let squares = input
+        .lines()
+        .map(|line| line.chars().collect_vec())
+        .collect_vec();
+    let elevation = |square: char| match square {
+        'S' => b'a',
+        'E' => b'z',
+        square => square as u8,
+    };
+    let (rows, cols) = (squares.len(), squares[0].len());
+    let find = |target: char| {
+        (0..rows)
+            .cartesian_product(0..cols)
+            .filter(|(row, col)| squares[*row][*col] == target)
+            .collect_vec()
+    };
+
+    let steps_to_end = |start: (usize, usize)| {
+        let mut steps = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some((row, col)) = queue.pop_front() {
+            if squares[row][col] == 'E' {
+                return Some(steps[&(row, col)]);
+            }
+            let neighbours = [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ];
+            for (next_row, next_col) in neighbours {
+                if next_row >= rows || next_col >= cols || steps.contains_key(&(next_row, next_col))
+                {
+                    continue;
+                }
+                if elevation(squares[next_row][next_col]) <= elevation(squares[row][col]) + 1 {
+                    steps.insert((next_row, next_col), steps[&(row, col)] + 1);
+                    queue.push_back((next_row, next_col));
+                }
+            }
+        }
+        None
+    };
+
+    let from_start = steps_to_end(find('S')[0]).unwrap();
+    let from_any = [find('S'), find('a')]
+        .concat()
+        .into_iter()
+        .filter_map(steps_to_end)
+        .min()
+        .unwrap();
+    (from_start, from_any)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ReferenceValue {
+    Integer(u32),
+    List(Vec<ReferenceValue>),
+}
+
+impl ReferenceValue {
+    fn parse(chars: &mut Peekable<Chars>) -> ReferenceValue {
+        if chars.next_if_eq(&'[').is_some() {
+            let mut items = vec![];
+            while chars.next_if_eq(&']').is_none() {
+                items.push(ReferenceValue::parse(chars));
+                chars.next_if_eq(&',');
+            }
+            ReferenceValue::List(items)
+        } else {
+            let mut digits = String::new();
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                digits.push(digit);
+            }
+            ReferenceValue::Integer(digits.parse().unwrap())
+        }
+    }
+}
+
+/// Integers compare as numbers and lists item by item, then by length. An integer compared with a list is
+/// first wrapped in a list.
+impl Ord for ReferenceValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (ReferenceValue::Integer(left), ReferenceValue::Integer(right)) => left.cmp(right),
+            (ReferenceValue::List(left), ReferenceValue::List(right)) => left.cmp(right),
+            (ReferenceValue::Integer(_), ReferenceValue::List(right)) => {
+                [self.clone()][..].cmp(&right[..])
+            }
+            (ReferenceValue::List(left), ReferenceValue::Integer(_)) => {
+                left[..].cmp(&[other.clone()][..])
+            }
+        }
+    }
+}
+
+impl PartialOrd for ReferenceValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Identical packets count as in order, and dividers are placed after packets equal to them, like the solver.
+fn reference_day13(input: &str) -> (usize, usize) {
+    let packets = input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| ReferenceValue::parse(&mut line.chars().peekable()))
+        .collect_vec();
+
+    let in_order = packets
+        .chunks(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0] <= pair[1])
+        .map(|(i, _)| i + 1)
+        .sum();
+
+    let divider = |text: &str| ReferenceValue::parse(&mut text.chars().peekable());
+    let (first, second) = (divider("[[2]]"), divider("[[6]]"));
+    let n_before =
+        |divider: &ReferenceValue| packets.iter().filter(|packet| *packet <= divider).count();
+    let decoder_key = (n_before(&first) + 1) * (n_before(&second) + 2);
+    (in_order, decoder_key)
+}
+
+#[test]
+fn day7_matches_reference() {
+    // Large enough that the files use more than 40000000, so part 2 must pick a directory to delete.
+    check_against_reference(7, 300, reference_day7);
+}
+
+#[test]
+fn day8_matches_reference() {
+    check_against_reference(8, 12, reference_day8);
+}
+
+#[test]
+fn day9_matches_reference() {
+    check_against_reference(9, 40, reference_day9);
+}
+
+#[test]
+fn day11_matches_reference() {
+    check_against_reference(11, 4, reference_day11);
+}
+
+#[test]
+fn day12_matches_reference() {
+    check_against_reference(12, 8, reference_day12);
+}
+
+#[test]
+fn day13_matches_reference() {
+    check_against_reference(13, 30, reference_day13);
+}
@@ -0,0 +1,61 @@
+//! Tests for every example input in `data/`, found by scanning the directory when the tests run.
+//! Add an example by dropping `data/test_day_{day}_{n}.txt` into `data/` and its answers into
+//! `data/test_answers.toml`.
+
+use std::fs;
+
+use aoc_2022::{
+    days::registry,
+    verify::{self, ExampleAnswers, Verdict, EXAMPLE_ANSWERS_FILE},
+    Part,
+};
+
+/// Examples and their days, named like `data/test_day_{day}_{n}.txt`.
+fn examples() -> Vec<(String, u8)> {
+    let mut examples = fs::read_dir("data")
+        .unwrap_or_else(|err| panic!("Can't read the data directory. {err}"))
+        .filter_map(|entry| {
+            let fname = entry.ok()?.file_name().into_string().ok()?;
+            let example = fname.strip_suffix(".txt")?.to_string();
+            let day = example
+                .strip_prefix("test_day_")?
+                .split('_')
+                .next()?
+                .parse::<u8>()
+                .ok()?;
+            Some((example, day))
+        })
+        .collect::<Vec<(String, u8)>>();
+    examples.sort();
+    examples
+}
+
+/// Solve a part of an example and check it against the example's expected answer.
+fn check_example(expected: &ExampleAnswers, example: &str, day: u8, part: Part) -> Verdict {
+    let puzzle = registry::get(day).unwrap_or_else(|| panic!("No solution for day {day}."));
+    let input = fs::read_to_string(format!("data/{example}.txt"))
+        .unwrap_or_else(|err| panic!("Can't read {example}. {err}"));
+
+    let answer = puzzle.parse(&input).and_then(|solver| solver.solve(part));
+    verify::verdict(expected.get(example, part), &answer)
+}
+
+#[test]
+fn examples_match_answers() {
+    let expected = ExampleAnswers::from_file(EXAMPLE_ANSWERS_FILE)
+        .unwrap_or_else(|err| panic!("Can't load {EXAMPLE_ANSWERS_FILE}. {err}"));
+    let examples = examples();
+    assert!(!examples.is_empty(), "No examples in the data directory.");
+
+    let failures = examples
+        .iter()
+        .flat_map(|(example, day)| Part::ALL.map(|part| (example, *day, part)))
+        .filter_map(
+            |(example, day, part)| match check_example(&expected, example, day, part) {
+                Verdict::Pass => None,
+                verdict => Some(format!("{example} part {part}: {verdict}")),
+            },
+        )
+        .collect::<Vec<String>>();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,453 @@
+//! Property tests over randomly generated, valid puzzle inputs.
+//! Each day has a generator of its puzzle model, which is rendered to text in the puzzle's format and
+//! parsed back. The parsed input must match the model and the solvers must keep the day's invariants.
+
+use itertools::Itertools;
+use proptest::{collection::vec, prelude::*, sample::select};
+
+use aoc_2022::{
+    days::{
+        day1, day10, day10::Operation, day11, day12, day13, day2, day3, day4, day5, day6, day7,
+        day8, day9,
+    },
+    Barrel, Direction, Monkey, Packet, Point, Rope,
+};
+
+/// Elves' food items, each a number of calories.
+fn elves() -> impl Strategy<Value = Vec<Vec<usize>>> {
+    vec(vec(0..100_000usize, 1..5), 1..10)
+}
+
+fn render_elves(elves: &[Vec<usize>]) -> String {
+    elves
+        .iter()
+        .map(|items| items.iter().join("\n"))
+        .join("\n\n")
+}
+
+/// Rounds of an opponent's move and a response.
+fn guide() -> impl Strategy<Value = Vec<(&'static str, &'static str)>> {
+    vec(
+        (select(vec!["A", "B", "C"]), select(vec!["X", "Y", "Z"])),
+        0..30,
+    )
+}
+
+/// Rucksacks with two equally sized compartments.
+fn sacks() -> impl Strategy<Value = Vec<String>> {
+    vec("([a-zA-Z]{2}){1,12}", 0..12)
+}
+
+/// Pairs of inclusive section ranges.
+fn section_pairs() -> impl Strategy<Value = Vec<((usize, usize), (usize, usize))>> {
+    let range = (1..100usize, 0..20usize).prop_map(|(start, len)| (start, start + len));
+    vec((range.clone(), range), 1..20)
+}
+
+/// Number of crates to move, and the stacks to move them from and to.
+type MoveModel = (usize, usize, usize);
+
+/// Stacks of crates, bottom first, and moves between them.
+fn supplies() -> impl Strategy<Value = (Vec<Vec<char>>, Vec<MoveModel>)> {
+    (1..=9usize).prop_flat_map(|n_stacks| {
+        let stack = vec(select(('A'..='Z').collect_vec()), 0..6);
+        let crate_move = (0..5usize, 1..=n_stacks, 1..=n_stacks);
+        (vec(stack, n_stacks), vec(crate_move, 1..20))
+    })
+}
+
+fn render_supplies(stacks: &[Vec<char>], moves: &[MoveModel]) -> String {
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut lines = (0..height)
+        .rev()
+        .map(|level| {
+            stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(item) => format!("[{item}]"),
+                    None => "   ".to_string(),
+                })
+                .join(" ")
+        })
+        .collect_vec();
+    lines.push((1..=stacks.len()).map(|num| format!(" {num} ")).join(" "));
+    lines.push(String::new());
+    lines.extend(
+        moves
+            .iter()
+            .map(|(n, from, to)| format!("move {n} from {from} to {to}")),
+    );
+    lines.join("\n")
+}
+
+/// Directory tree of file sizes.
+#[derive(Debug, Clone)]
+struct Tree {
+    files: Vec<usize>,
+    dirs: Vec<Tree>,
+}
+
+impl Tree {
+    fn n_dirs(&self) -> usize {
+        1 + self.dirs.iter().map(Tree::n_dirs).sum::<usize>()
+    }
+
+    fn size(&self) -> usize {
+        self.files.iter().sum::<usize>() + self.dirs.iter().map(Tree::size).sum::<usize>()
+    }
+
+    /// Terminal output of listing this directory and then each of its subdirectories.
+    fn session(&self, lines: &mut Vec<String>) {
+        lines.push("$ ls".to_string());
+        lines.extend((0..self.dirs.len()).map(|i| format!("dir d{i}")));
+        lines.extend(
+            self.files
+                .iter()
+                .enumerate()
+                .map(|(i, size)| format!("{size} f{i}.txt")),
+        );
+        for (i, dir) in self.dirs.iter().enumerate() {
+            lines.push(format!("$ cd d{i}"));
+            dir.session(lines);
+            lines.push("$ cd ..".to_string());
+        }
+    }
+}
+
+fn tree() -> impl Strategy<Value = Tree> {
+    let files = || vec(1..100_000usize, 0..4);
+    let leaf = files().prop_map(|files| Tree {
+        files,
+        dirs: vec![],
+    });
+    leaf.prop_recursive(3, 24, 3, move |dir| {
+        (files(), vec(dir, 0..3)).prop_map(|(files, dirs)| Tree { files, dirs })
+    })
+}
+
+/// Rows of tree heights.
+fn forest() -> impl Strategy<Value = Vec<Vec<u32>>> {
+    (1..8usize, 1..8usize).prop_flat_map(|(rows, cols)| vec(vec(0..10u32, cols), rows))
+}
+
+fn motions() -> impl Strategy<Value = Vec<(Direction, usize)>> {
+    vec((select(Direction::ALL.to_vec()), 1..10usize), 0..30)
+}
+
+fn direction_label(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "U",
+        Direction::Right => "R",
+        Direction::Down => "D",
+        Direction::Left => "L",
+    }
+}
+
+/// Programs long enough to run past the last pixel of the screen.
+fn program() -> impl Strategy<Value = Vec<Operation>> {
+    let operation = prop_oneof![
+        Just(Operation::NoOp),
+        (-40..40isize).prop_map(Operation::Add),
+    ];
+    vec(operation, 0..300)
+}
+
+/// Monkey's number, items, operation, test divisor and the monkeys thrown to.
+type MonkeyModel = (usize, Vec<usize>, String, usize, usize, usize);
+
+/// Monkeys numbered in order. Most throw to other monkeys, but some throw to themselves or to a
+/// monkey past the last one, which the parser must reject.
+fn monkeys() -> impl Strategy<Value = Vec<MonkeyModel>> {
+    (2..=6usize).prop_flat_map(|n_monkeys| {
+        let operand = prop_oneof![
+            Just("old".to_string()),
+            (1..20usize).prop_map(|n| n.to_string())
+        ];
+        let operation = (operand, select(vec!["+", "-", "*"]))
+            .prop_map(|(operand, operator)| format!("old {operator} {operand}"));
+        let divisor = select(vec![2, 3, 5, 7, 11, 13, 17, 19, 23]);
+        // Offset of 0 throws to itself and n past the last monkey.
+        let offset = || {
+            prop_oneof![
+                18 => 1..n_monkeys,
+                1 => Just(0),
+                1 => Just(n_monkeys),
+            ]
+        };
+        let monkey = (
+            vec(0..100usize, 0..5),
+            operation,
+            divisor,
+            offset(),
+            offset(),
+        );
+        vec(monkey, n_monkeys).prop_map(move |monkeys| {
+            monkeys
+                .into_iter()
+                .enumerate()
+                .map(|(num, (items, operation, divisor, on_true, on_false))| {
+                    let target = |offset: usize| {
+                        if offset == n_monkeys {
+                            n_monkeys
+                        } else {
+                            (num + offset) % n_monkeys
+                        }
+                    };
+                    (
+                        num,
+                        items,
+                        operation,
+                        divisor,
+                        target(on_true),
+                        target(on_false),
+                    )
+                })
+                .collect_vec()
+        })
+    })
+}
+
+fn render_monkey((num, items, operation, divisor, on_true, on_false): &MonkeyModel) -> String {
+    format!(
+        "Monkey {num}:\n  \
+         Starting items: {}\n  \
+         Operation: new = {operation}\n  \
+         Test: divisible by {divisor}\n    \
+         If true: throw to monkey {on_true}\n    \
+         If false: throw to monkey {on_false}",
+        items.iter().join(", ")
+    )
+}
+
+/// Heightmap with a start and an end on distinct squares.
+fn heightmap() -> impl Strategy<Value = Vec<Vec<char>>> {
+    (1..6usize, 2..6usize).prop_flat_map(|(rows, cols)| {
+        let squares = vec(select(('a'..='z').collect_vec()), rows * cols);
+        let marks = (0..rows * cols, 1..rows * cols);
+        (squares, marks).prop_map(move |(mut squares, (start, offset))| {
+            squares[start] = 'S';
+            squares[(start + offset) % (rows * cols)] = 'E';
+            squares.chunks(cols).map(<[char]>::to_vec).collect_vec()
+        })
+    })
+}
+
+/// Packet text like `[1,[2,[]],3]`.
+fn packet() -> impl Strategy<Value = String> {
+    let item = (0..=10u32).prop_map(|item| item.to_string());
+    let value = item.prop_recursive(4, 32, 4, |value| {
+        vec(value, 0..4).prop_map(|items| format!("[{}]", items.join(",")))
+    });
+    vec(value, 0..5).prop_map(|items| format!("[{}]", items.join(",")))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn day1_round_trip(elves in elves()) {
+        let calories = day1::parse_calories(&render_elves(&elves)).unwrap();
+        let totals = elves.iter().map(|items| items.iter().sum::<usize>()).collect_vec();
+        prop_assert_eq!(&calories, &totals);
+        prop_assert_eq!(day1::top_calories(&calories, 1), totals.iter().max().copied().unwrap());
+    }
+
+    #[test]
+    fn day2_scores_in_range(rounds in guide()) {
+        let text = rounds.iter().map(|(exp, resp)| format!("{exp} {resp}")).join("\n");
+        let guide = day2::parse_guide(&text).unwrap();
+        prop_assert_eq!(
+            guide.iter().map(|(exp, resp)| (exp.as_str(), resp.as_str())).collect_vec(),
+            rounds.clone()
+        );
+        // Each round scores 1 to 3 for the shape plus 0, 3 or 6 for the outcome.
+        for score in [day2::score_moves(&guide).unwrap(), day2::score_outcomes(&guide).unwrap()] {
+            prop_assert!((rounds.len()..=9 * rounds.len()).contains(&score));
+        }
+    }
+
+    #[test]
+    fn day3_round_trip(sacks in sacks()) {
+        let parsed = day3::parse_sacks(&sacks.join("\n")).unwrap();
+        prop_assert_eq!(&parsed, &sacks);
+        prop_assert!(day3::sack_priorities(&parsed) <= 52 * sacks.len());
+    }
+
+    #[test]
+    fn day4_contained_pairs_overlap(pairs in section_pairs()) {
+        let text = pairs
+            .iter()
+            .map(|((s1, e1), (s2, e2))| format!("{s1}-{e1},{s2}-{e2}"))
+            .join("\n");
+        let assignments = day4::parse_assignments(&text).unwrap();
+        for (((s1, e1), (s2, e2)), (a1, a2)) in pairs.iter().zip(assignments.iter()) {
+            prop_assert_eq!(a1, &(*s1..=*e1));
+            prop_assert_eq!(a2, &(*s2..=*e2));
+        }
+        prop_assert!(day4::count_duplicates(&assignments) <= day4::count_overlaps(&assignments));
+    }
+
+    #[test]
+    fn day5_crates_conserved((stacks, moves) in supplies()) {
+        let supplies = day5::parse_supplies(&render_supplies(&stacks, &moves)).unwrap();
+        prop_assert_eq!(
+            supplies.stacks.iter().map(|stack| stack.iter().copied().collect_vec()).collect_vec(),
+            stacks.clone()
+        );
+        prop_assert_eq!(
+            supplies
+                .moves
+                .iter()
+                .map(|mv| (mv.n_crates, mv.from_stack_idx, mv.to_stack_idx))
+                .collect_vec(),
+            moves.clone()
+        );
+
+        // Moves that take more crates than a stack has can't be done.
+        let mut heights = stacks.iter().map(Vec::len).collect_vec();
+        let fits = moves.iter().all(|(n, from, to)| {
+            let fits = heights[from - 1] >= *n;
+            heights[from - 1] = heights[from - 1].saturating_sub(*n);
+            heights[to - 1] += n;
+            fits
+        });
+        let all_crates = |stacks: &[_]| stacks.iter().flatten().copied().sorted().collect_vec();
+        let before = all_crates(&supplies.stacks);
+        for rearranged in [day5::rearrange_9000(&supplies), day5::rearrange_9001(&supplies)] {
+            match rearranged {
+                Ok(rearranged) if fits => prop_assert_eq!(&all_crates(&rearranged), &before),
+                rearranged => prop_assert_eq!(rearranged.is_ok(), fits),
+            }
+        }
+    }
+
+    #[test]
+    fn day6_packet_marker_precedes_message(buffer in "[a-p]{0,60}") {
+        if let Ok(message) = day6::message_marker(&buffer) {
+            let packet = day6::packet_marker(&buffer).unwrap();
+            prop_assert!(packet <= message);
+        }
+        if let Ok(packet) = day6::packet_marker(&buffer) {
+            prop_assert_eq!(buffer[packet - 4..packet].chars().unique().count(), 4);
+        }
+    }
+
+    #[test]
+    fn day7_sizes_add_up(tree in tree()) {
+        let mut lines = vec!["$ cd /".to_string()];
+        tree.session(&mut lines);
+        let file_system = lines.join("\n").parse::<day7::FileSystem>().unwrap();
+
+        prop_assert_eq!(file_system.dirs().len(), tree.n_dirs());
+        prop_assert_eq!(file_system.dirs()[0].du().unwrap(), tree.size());
+        for dir in file_system.dirs() {
+            let children = dir.children().iter().map(|child| child.du().unwrap()).sum::<usize>();
+            prop_assert!(dir.du().unwrap() >= children);
+        }
+    }
+
+    #[test]
+    fn day8_edges_visible(heights in forest()) {
+        let text = heights.iter().map(|row| row.iter().join("")).join("\n");
+        let forest = day8::parse_forest(&text).unwrap();
+        let (rows, cols) = (heights.len(), heights[0].len());
+        prop_assert_eq!((forest.rows(), forest.cols()), (rows, cols));
+        prop_assert_eq!(forest.iter().map(|(_, height)| *height).collect_vec(), heights.concat());
+
+        let edges = if rows <= 2 || cols <= 2 { rows * cols } else { 2 * (rows + cols) - 4 };
+        prop_assert!((edges..=rows * cols).contains(&day8::count_visible_trees(&forest)));
+    }
+
+    #[test]
+    fn day9_rope_stays_adjacent(motions in motions(), n_segments in 2..=10usize) {
+        let text = motions
+            .iter()
+            .map(|(direction, steps)| format!("{} {steps}", direction_label(*direction)))
+            .join("\n");
+        prop_assert_eq!(&day9::parse_motions(&text).unwrap(), &motions);
+
+        let mut rope = Rope::new(n_segments);
+        for (direction, steps) in motions {
+            for _ in 0..steps {
+                rope.move_rope(direction);
+                let segments = rope.segments();
+                prop_assert_eq!(segments.len(), n_segments);
+                for (prev, next) in segments.iter().tuple_windows::<(&Point, &Point)>() {
+                    prop_assert!(prev.chebyshev(*next) <= 1, "{segments:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn day10_screen_size_constant(program in program()) {
+        let text = program.iter().join("\n");
+        prop_assert_eq!(&day10::parse_program(&text).unwrap(), &program);
+
+        let rows = day10::render_program(&program).unwrap();
+        prop_assert_eq!(rows.len(), 6);
+        for row in rows {
+            prop_assert_eq!(row.chars().count(), 40);
+        }
+    }
+
+    #[test]
+    fn day11_items_conserved(models in monkeys(), n_rounds in 1..20usize) {
+        let monkeys = models
+            .iter()
+            .map(|model| Monkey::new(&render_monkey(model)))
+            .collect::<Result<Vec<Monkey>, _>>()
+            .unwrap();
+        for (monkey, (num, items, ..)) in monkeys.iter().zip(models.iter()) {
+            prop_assert_eq!(monkey.num(), *num);
+            prop_assert_eq!(monkey.items(), items.as_slice());
+        }
+        let text = models.iter().map(render_monkey).join("\n\n");
+        let parsed = day11::parse_monkeys(&text);
+        let throws_to_others = models.iter().all(|(num, .., on_true, on_false)| {
+            [on_true, on_false].iter().all(|target| *target != num && **target < models.len())
+        });
+        if !throws_to_others {
+            prop_assert!(parsed.is_err());
+            return Ok(());
+        }
+        prop_assert_eq!(parsed.unwrap().len(), models.len());
+
+        // Without relief, worry levels are kept small by the least common multiple of the divisors.
+        let n_items = |monkeys: &[Monkey]| monkeys.iter().map(|monkey| monkey.items().len()).sum::<usize>();
+        let mut barrel = Barrel::from_monkeys(monkeys.clone(), false);
+        for _ in 0..n_rounds {
+            barrel.start_round().unwrap();
+            prop_assert_eq!(n_items(&barrel.monkeys()), n_items(&monkeys));
+        }
+    }
+
+    #[test]
+    fn day12_any_start_is_shortest(squares in heightmap()) {
+        let text = squares.iter().map(|row| row.iter().collect::<String>()).join("\n");
+        let heightmap = day12::parse_heightmap(&text).unwrap();
+        prop_assert_eq!(heightmap.iter().map(|(_, square)| *square).collect_vec(), squares.concat());
+
+        if let Ok(steps) = day12::fewest_steps(&heightmap) {
+            let start = heightmap.position(|square| *square == 'S').unwrap();
+            let end = heightmap.position(|square| *square == 'E').unwrap();
+            let distance = Point::from_grid_index(start).manhattan(Point::from_grid_index(end));
+            prop_assert!(steps >= distance);
+            prop_assert!(day12::fewest_steps_any_start(&heightmap).unwrap() <= steps);
+        }
+    }
+
+    #[test]
+    fn day13_round_trip(left in packet(), right in packet()) {
+        let (left_packet, right_packet) = (Packet::new(&left).unwrap(), Packet::new(&right).unwrap());
+        prop_assert_eq!(left_packet.to_string(), left.clone());
+        prop_assert_eq!(right_packet.to_string(), right.clone());
+        prop_assert_eq!(
+            Packet::compare(&left_packet, &right_packet),
+            Packet::compare(&right_packet, &left_packet).reverse()
+        );
+
+        let pairs = day13::parse_packet_pairs(&format!("{left}\n{right}\n")).unwrap();
+        prop_assert_eq!(pairs.len(), 1);
+    }
+}